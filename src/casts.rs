@@ -0,0 +1,58 @@
+use crate::column_type::ColumnType;
+use crate::errors::Errors;
+use crate::sql_data_type::SqlDataType;
+
+/// Parse a `column=type` override, such as the ones given to `--cast`.
+pub fn parse_cast(string: &str) -> Result<(String, String), Errors> {
+    match string.find('=') {
+        Some(index) if index > 0 && !string[(index + 1)..].trim().is_empty() => Ok((
+            string[..index].trim().to_string(),
+            string[(index + 1)..].trim().to_string(),
+        )),
+        _ => Err(Errors::InvalidCastError(string.to_string())),
+    }
+}
+
+/// The type of a column once Vertica has cast it to `type_name`, e.g. `numeric(12,2)`.
+/// Lengths, precisions and scales that aren't given take Vertica's defaults.
+pub(crate) fn cast_column_type(name: &str, type_name: &str) -> ColumnType {
+    let data_type = SqlDataType::from_string(type_name);
+
+    let args: Vec<u32> = match (type_name.find('('), type_name.rfind(')')) {
+        (Some(start), Some(end)) if start < end => type_name[(start + 1)..end]
+            .split(',')
+            .filter_map(|arg| arg.trim().parse::<u32>().ok())
+            .collect(),
+        _ => vec![],
+    };
+
+    let arg = |index: usize| args.get(index).cloned();
+
+    let (width, precision, scale) = match data_type {
+        SqlDataType::Char | SqlDataType::Binary => (arg(0).unwrap_or(1), None, None),
+        SqlDataType::Varchar | SqlDataType::Varbinary => (arg(0).unwrap_or(80), None, None),
+        SqlDataType::LongVarchar | SqlDataType::LongVarbinary => {
+            (arg(0).unwrap_or(1_048_576), None, None)
+        }
+        SqlDataType::Numeric => {
+            let precision = arg(0).unwrap_or(37) as u16;
+            let scale = arg(1).unwrap_or(if args.is_empty() { 15 } else { 0 }) as u16;
+            (0, Some(precision), Some(scale))
+        }
+        SqlDataType::Boolean => (1, None, None),
+        SqlDataType::Uuid => (16, None, None),
+        SqlDataType::Time
+        | SqlDataType::TimeTz
+        | SqlDataType::Timestamp
+        | SqlDataType::TimestampTz => (8, arg(0).map(|p| p as u16), None),
+        _ => (8, None, None),
+    };
+
+    ColumnType {
+        name: name.to_string(),
+        data_type,
+        width,
+        precision,
+        scale,
+    }
+}
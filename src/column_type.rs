@@ -1,10 +1,12 @@
+use crate::describe::ColumnDescription;
+use crate::numeric::numeric_width;
 use crate::sql_data_type::SqlDataType;
 
 #[derive(Debug)]
 pub struct ColumnType {
     pub(crate) name: String,
     pub(crate) data_type: SqlDataType,
-    pub(crate) width: u16,
+    pub(crate) width: u32,
     pub(crate) precision: Option<u16>,
     pub(crate) scale: Option<u16>,
 }
@@ -31,7 +33,52 @@ impl ColumnType {
         ColumnType {
             name: values[0].clone(),
             data_type: SqlDataType::from_string(values[1].clone().as_str()),
-            width: values[2].parse::<u16>().unwrap(),
+            width: values[2].parse::<u32>().unwrap(),
+            precision,
+            scale,
+        }
+    }
+
+    /// Build a column type from the result-set metadata returned by `SQLDescribeCol`,
+    /// for when there is no catalog entry to consult (e.g. an arbitrary query).
+    pub(crate) fn from_description(description: &ColumnDescription) -> ColumnType {
+        let data_type = SqlDataType::from_odbc(description.data_type).unwrap_or_else(|| {
+            SqlDataType::Unsupported(format!("ODBC type {}", description.data_type))
+        });
+
+        let column_size = description.column_size as u32;
+        let decimal_digits = if description.decimal_digits > 0 {
+            Some(description.decimal_digits as u16)
+        } else {
+            None
+        };
+
+        let (width, precision, scale) = match data_type {
+            SqlDataType::Char | SqlDataType::Binary => (column_size, None, None),
+            SqlDataType::Varchar
+            | SqlDataType::Varbinary
+            | SqlDataType::LongVarchar
+            | SqlDataType::LongVarbinary => (column_size, None, None),
+            SqlDataType::Numeric => (
+                numeric_width(Some(column_size as u16)) as u32,
+                Some(column_size as u16),
+                Some(description.decimal_digits.max(0) as u16),
+            ),
+            SqlDataType::Boolean => (1, None, None),
+            SqlDataType::Uuid => (16, None, None),
+            SqlDataType::Time
+            | SqlDataType::TimeTz
+            | SqlDataType::Timestamp
+            | SqlDataType::TimestampTz
+            | SqlDataType::Interval(_)
+            | SqlDataType::IntervalYearMonth(_) => (8, decimal_digits, None),
+            _ => (8, None, None),
+        };
+
+        ColumnType {
+            name: description.name.clone(),
+            data_type,
+            width,
             precision,
             scale,
         }
@@ -0,0 +1,25 @@
+/// Everything needed to connect to a Vertica database.
+#[derive(Debug, Clone)]
+pub struct ConnectionOptions {
+    pub server: String,
+    pub port: u16,
+    pub database: String,
+    pub username: String,
+    pub password: Option<String>,
+}
+
+impl ConnectionOptions {
+    pub(crate) fn dsn(&self) -> String {
+        format!(
+            "Driver=Vertica;ServerName={};Port={};Database={};UID={}{}",
+            self.server,
+            self.port,
+            self.database,
+            self.username,
+            match &self.password {
+                None => "".to_string(),
+                Some(password) => format!(";PWD={}", password),
+            }
+        )
+    }
+}
@@ -0,0 +1,457 @@
+use std::error::Error;
+
+use odbc::odbc_safe::AutocommitOn;
+use odbc::{ffi, Allocated, Cursor};
+use serde::Deserialize;
+
+use crate::column_type::ColumnType;
+use crate::errors::Errors;
+use crate::extract_options::ExtractOptions;
+use crate::long_data::read_long_data;
+use crate::sql_data_type::SqlDataType;
+use crate::{decode_text, fit_to_width};
+
+/// When to enclose a CSV value in quotes.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Quoting {
+    /// Only values that need it: those holding the delimiter, the quote, a line break,
+    /// or that would otherwise be read as `NULL`.
+    Necessary,
+    Always,
+    /// No values; one that would need quotes fails the extraction.
+    Never,
+}
+
+/// How binary values are written as text.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BinaryEncoding {
+    /// Two hex digits per byte, which Vertica loads with `FORMAT 'hex'`.
+    Hex,
+    /// Standard base64, which `COPY` can't read; for files meant for other tools.
+    Base64,
+    /// A `\ooo` escape per byte, which is how Vertica reads binary text by default.
+    Octal,
+}
+
+/// Settings for CSV output. The defaults write files that Vertica can load back
+/// unchanged with
+/// `COPY ... DELIMITER ',' ENCLOSED BY '"' NULL '' NO ESCAPE`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CsvOptions {
+    /// An ASCII character, as Vertica requires.
+    pub delimiter: char,
+    /// An ASCII character, as Vertica requires.
+    pub quote: char,
+    pub quoting: Quoting,
+    /// What `NULL` is written as.
+    pub null: String,
+    /// Start the file with a row of column names.
+    pub header: bool,
+    /// A Vertica `TO_CHAR` pattern for dates, e.g. `MM/DD/YYYY`; ISO format if `None`.
+    pub date_format: Option<String>,
+    /// A Vertica `TO_CHAR` pattern for timestamps; ISO format if `None`.
+    pub timestamp_format: Option<String>,
+    pub binary_encoding: BinaryEncoding,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        CsvOptions {
+            delimiter: ',',
+            quote: '"',
+            quoting: Quoting::Necessary,
+            null: "".to_string(),
+            header: false,
+            date_format: None,
+            timestamp_format: None,
+            binary_encoding: BinaryEncoding::Octal,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Check that Vertica could load what these options write: the delimiter and quote
+    /// must be different ASCII characters.
+    pub fn validate(&self) -> Result<(), String> {
+        if !self.delimiter.is_ascii() || !self.quote.is_ascii() {
+            return Err("the delimiter and quote must be ASCII characters".to_string());
+        }
+
+        if self.delimiter == self.quote {
+            return Err("the delimiter and quote must be different".to_string());
+        }
+
+        Ok(())
+    }
+}
+
+impl Quoting {
+    /// Parse a `--quoting` value: `necessary`, `always` or `never`.
+    pub fn parse(string: &str) -> Option<Quoting> {
+        match string.trim().to_lowercase().as_str() {
+            "necessary" => Some(Quoting::Necessary),
+            "always" => Some(Quoting::Always),
+            "never" => Some(Quoting::Never),
+            _ => None,
+        }
+    }
+}
+
+impl BinaryEncoding {
+    /// Parse a `--binary-encoding` value: `hex`, `base64` or `octal`.
+    pub fn parse(string: &str) -> Option<BinaryEncoding> {
+        match string.trim().to_lowercase().as_str() {
+            "hex" => Some(BinaryEncoding::Hex),
+            "base64" => Some(BinaryEncoding::Base64),
+            "octal" => Some(BinaryEncoding::Octal),
+            _ => None,
+        }
+    }
+
+    fn encode(self, bytes: &[u8]) -> Vec<u8> {
+        match self {
+            BinaryEncoding::Hex => bytes
+                .iter()
+                .flat_map(|b| format!("{:02x}", b).into_bytes())
+                .collect(),
+            BinaryEncoding::Octal => bytes
+                .iter()
+                .flat_map(|b| format!("\\{:03o}", b).into_bytes())
+                .collect(),
+            BinaryEncoding::Base64 => base64::encode(bytes).into_bytes(),
+        }
+    }
+}
+
+/// The row of column names that starts a CSV file.
+pub(crate) fn create_csv_header(
+    column_types: &[ColumnType],
+    csv: &CsvOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let names: Vec<Option<Vec<u8>>> = column_types
+        .iter()
+        .map(|column_type| Some(column_type.name.clone().into_bytes()))
+        .collect();
+
+    create_csv_row(&names, column_types, csv)
+}
+
+/// Read the current row as CSV. Most values are written as Vertica formats them as
+/// text, which it can read back; floats are formatted here, so they round-trip
+/// exactly, and binary values are encoded as `csv.binary_encoding` says.
+pub(crate) fn read_csv_row(
+    cursor: &mut Cursor<Allocated, AutocommitOn>,
+    handle: ffi::SQLHSTMT,
+    column_types: &[ColumnType],
+    options: &ExtractOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut values: Vec<Option<Vec<u8>>> = vec![];
+    let binary_encoding = options.csv.binary_encoding;
+
+    for (index, col_type) in column_types.iter().enumerate() {
+        let i = (index + 1) as u16;
+
+        let value = match col_type.data_type {
+            SqlDataType::Integer => cursor
+                .get_data::<i64>(i)?
+                .map(|v| v.to_string().into_bytes()),
+            SqlDataType::Float => cursor
+                .get_data::<f64>(i)?
+                .map(|v| format_float(v).into_bytes()),
+            SqlDataType::Boolean => {
+                cursor
+                    .get_data::<bool>(i)?
+                    .map(|b| if b { b"t".to_vec() } else { b"f".to_vec() })
+            }
+            SqlDataType::Char | SqlDataType::Varchar => match cursor.get_data::<Vec<u8>>(i)? {
+                None => None,
+                Some(value) => Some(decode_text(value, col_type, options.encoding)?),
+            },
+            SqlDataType::LongVarchar => match read_long(handle, i, col_type)? {
+                None => None,
+                Some(value) => Some(decode_text(value, col_type, options.encoding)?),
+            },
+            SqlDataType::Binary => match cursor.get_data::<Vec<u8>>(i)? {
+                None => None,
+                Some(value) => Some(binary_encoding.encode(&fit_to_width(value, 0, col_type)?)),
+            },
+            SqlDataType::Varbinary => cursor
+                .get_data::<Vec<u8>>(i)?
+                .map(|value| binary_encoding.encode(&value)),
+            SqlDataType::LongVarbinary | SqlDataType::Geometry | SqlDataType::Geography => {
+                read_long(handle, i, col_type)?.map(|value| binary_encoding.encode(&value))
+            }
+            SqlDataType::Unsupported(_) => {
+                unreachable!("unsupported columns are dealt with before fetching")
+            }
+            // everything else is written as Vertica's own text
+            _ => cursor
+                .get_data::<&str>(i)?
+                .map(|value| value.as_bytes().to_vec()),
+        };
+
+        values.push(value);
+    }
+
+    create_csv_row(&values, column_types, &options.csv)
+}
+
+fn read_long(
+    handle: ffi::SQLHSTMT,
+    i: u16,
+    col_type: &ColumnType,
+) -> Result<Option<Vec<u8>>, Errors> {
+    read_long_data(handle, i).map_err(|_| Errors::ReadError(col_type.name.clone()))
+}
+
+/// The shortest text that reads back as the same float, in a form Vertica accepts.
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() && value > 0.0 {
+        "Infinity".to_string()
+    } else if value.is_infinite() {
+        "-Infinity".to_string()
+    } else if value != 0.0 && (value.abs() >= 1e16 || value.abs() < 1e-5) {
+        format!("{:e}", value)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// A row of values, `None` being `NULL`, which is written as the null marker itself,
+/// unquoted; a value that equals the marker is quoted, so the two can be told apart.
+fn create_csv_row(
+    values: &[Option<Vec<u8>>],
+    column_types: &[ColumnType],
+    csv: &CsvOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let mut row: Vec<u8> = vec![];
+
+    for (index, value) in values.iter().enumerate() {
+        if index > 0 {
+            row.push(csv.delimiter as u8);
+        }
+
+        match value {
+            None => row.extend_from_slice(csv.null.as_bytes()),
+            Some(value) => write_field(&mut row, value, &column_types[index], csv)?,
+        }
+    }
+
+    row.push(b'\n');
+
+    Ok(row)
+}
+
+/// Append a value to the row, enclosed in quotes if `csv.quoting` calls for it, with
+/// any quotes inside it doubled.
+fn write_field(
+    row: &mut Vec<u8>,
+    value: &[u8],
+    column_type: &ColumnType,
+    csv: &CsvOptions,
+) -> Result<(), Errors> {
+    let delimiter = csv.delimiter as u8;
+    let quote = csv.quote as u8;
+
+    // unquoted, these would be read back as something else
+    let ambiguous = value == csv.null.as_bytes()
+        || value
+            .iter()
+            .any(|&b| b == delimiter || b == b'\n' || b == b'\r');
+
+    let quoted = match csv.quoting {
+        Quoting::Necessary => ambiguous || value.contains(&quote),
+        Quoting::Always => true,
+        Quoting::Never if ambiguous => {
+            return Err(Errors::UnquotableValueError(column_type.name.clone()));
+        }
+        Quoting::Never => false,
+    };
+
+    if !quoted {
+        row.extend_from_slice(value);
+        return Ok(());
+    }
+
+    row.push(quote);
+
+    for &b in value {
+        if b == quote {
+            row.push(quote);
+        }
+
+        row.push(b);
+    }
+
+    row.push(quote);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn columns(count: usize) -> Vec<ColumnType> {
+        (0..count)
+            .map(|i| ColumnType {
+                name: format!("c{}", i),
+                data_type: SqlDataType::Varchar,
+                width: 80,
+                precision: None,
+                scale: None,
+            })
+            .collect()
+    }
+
+    fn row(values: &[Option<&str>], csv: &CsvOptions) -> Result<String, String> {
+        let values: Vec<Option<Vec<u8>>> = values
+            .iter()
+            .map(|value| value.map(|v| v.as_bytes().to_vec()))
+            .collect();
+
+        create_csv_row(&values, &columns(values.len()), csv)
+            .map(|row| String::from_utf8(row).unwrap())
+            .map_err(|e| e.to_string())
+    }
+
+    fn options(quoting: Quoting, null: &str) -> CsvOptions {
+        CsvOptions {
+            quoting,
+            null: null.to_string(),
+            ..CsvOptions::default()
+        }
+    }
+
+    #[test]
+    fn nulls_and_empty_strings() {
+        let csv = CsvOptions::default();
+        assert_eq!(
+            row(&[None, Some(""), Some("x")], &csv).unwrap(),
+            ",\"\",x\n"
+        );
+
+        let csv = options(Quoting::Necessary, "\\N");
+        assert_eq!(
+            row(&[None, Some(""), Some("\\N")], &csv).unwrap(),
+            "\\N,,\"\\N\"\n"
+        );
+    }
+
+    #[test]
+    fn values_that_need_quotes() {
+        let csv = CsvOptions::default();
+
+        assert_eq!(row(&[Some("a,b")], &csv).unwrap(), "\"a,b\"\n");
+        assert_eq!(
+            row(&[Some("say \"hi\"")], &csv).unwrap(),
+            "\"say \"\"hi\"\"\"\n"
+        );
+        assert_eq!(row(&[Some("a\nb")], &csv).unwrap(), "\"a\nb\"\n");
+        assert_eq!(row(&[Some("a\rb")], &csv).unwrap(), "\"a\rb\"\n");
+        assert_eq!(row(&[Some("a b")], &csv).unwrap(), "a b\n");
+    }
+
+    #[test]
+    fn other_delimiters_and_quotes() {
+        let csv = CsvOptions {
+            delimiter: '|',
+            quote: '\'',
+            ..CsvOptions::default()
+        };
+
+        assert_eq!(
+            row(&[Some("a,b"), Some("a|b"), Some("it's")], &csv).unwrap(),
+            "a,b|'a|b'|'it''s'\n"
+        );
+    }
+
+    #[test]
+    fn always_quoting() {
+        let csv = options(Quoting::Always, "\\N");
+
+        assert_eq!(
+            row(&[None, Some(""), Some("x"), Some("\"")], &csv).unwrap(),
+            "\\N,\"\",\"x\",\"\"\"\"\n"
+        );
+    }
+
+    #[test]
+    fn never_quoting() {
+        let csv = options(Quoting::Never, "\\N");
+
+        assert_eq!(
+            row(&[None, Some(""), Some("x"), Some("say \"hi\"")], &csv).unwrap(),
+            "\\N,,x,say \"hi\"\n"
+        );
+    }
+
+    #[test]
+    fn values_that_can_not_go_unquoted() {
+        let csv = options(Quoting::Never, "\\N");
+
+        for value in &["a,b", "a\nb", "a\rb", "\\N"] {
+            let error = row(&[Some("x"), Some(value)], &csv).unwrap_err();
+            assert!(error.contains("column c1"), "{}", error);
+        }
+
+        // with the default null marker, that's an empty string
+        let csv = options(Quoting::Never, "");
+        assert!(row(&[Some("")], &csv).is_err());
+        assert_eq!(row(&[None], &csv).unwrap(), "\n");
+    }
+
+    #[test]
+    fn header() {
+        let csv = CsvOptions::default();
+        let header = create_csv_header(&columns(2), &csv).unwrap();
+
+        assert_eq!(String::from_utf8(header).unwrap(), "c0,c1\n");
+    }
+
+    #[test]
+    fn binary_encodings() {
+        let bytes = [0x00, 0xff, 0x10, b'a'];
+
+        assert_eq!(BinaryEncoding::Hex.encode(&bytes), b"00ff1061".to_vec());
+        assert_eq!(
+            BinaryEncoding::Octal.encode(&bytes),
+            b"\\000\\377\\020\\141".to_vec()
+        );
+        assert_eq!(BinaryEncoding::Base64.encode(&bytes), b"AP8QYQ==".to_vec());
+        assert_eq!(BinaryEncoding::Hex.encode(&[]), b"".to_vec());
+    }
+
+    #[test]
+    fn floats() {
+        assert_eq!(format_float(f64::NAN), "NaN");
+        assert_eq!(format_float(f64::INFINITY), "Infinity");
+        assert_eq!(format_float(f64::NEG_INFINITY), "-Infinity");
+        assert_eq!(format_float(0.1), "0.1");
+        assert_eq!(format_float(-2.5), "-2.5");
+        assert_eq!(format_float(0.0), "0");
+        assert_eq!(format_float(1e20), "1e20");
+        assert_eq!(format_float(1.5e-7), "1.5e-7");
+    }
+
+    #[test]
+    fn invalid_options() {
+        let same = CsvOptions {
+            quote: ',',
+            ..CsvOptions::default()
+        };
+        let not_ascii = CsvOptions {
+            delimiter: '§',
+            ..CsvOptions::default()
+        };
+
+        assert!(CsvOptions::default().validate().is_ok());
+        assert!(same.validate().is_err());
+        assert!(not_ascii.validate().is_err());
+    }
+}
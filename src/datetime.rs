@@ -0,0 +1,312 @@
+use crate::interval::seconds_to_microseconds;
+
+const MICROSECONDS_PER_DAY: i64 = 86_400_000_000;
+const SECONDS_PER_DAY: i64 = 86_400;
+
+/// What Vertica stores for `infinity` and `-infinity` dates and timestamps.
+const INFINITY: i64 = i64::MAX;
+const NEGATIVE_INFINITY: i64 = -i64::MAX;
+
+/// Parse a time of day, `HH:MM:SS` or `HH:MM:SS.ffffff`, into microseconds since
+/// midnight.
+pub(crate) fn parse_time(value: &str) -> Option<i64> {
+    let parts: Vec<&str> = value.trim().split(':').collect();
+
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let hours = parts[0].parse::<i64>().ok()?;
+    let minutes = parts[1].parse::<i64>().ok()?;
+    let micros = seconds_to_microseconds(parts[2])?;
+
+    Some(hours * 3_600_000_000 + minutes * 60_000_000 + micros)
+}
+
+/// Parse a time with a time zone, as Vertica writes it (e.g. `15:12:34.5-05` or
+/// `09:30:00+05:30`), into microseconds since midnight and the UTC offset in seconds
+/// east of Greenwich.
+pub(crate) fn parse_timetz(value: &str) -> Option<(i64, i64)> {
+    let value = value.trim();
+    let split = value.rfind(&['+', '-'][..])?;
+
+    let micros = parse_time(&value[..split])?;
+
+    let mut offset = 0;
+    let mut unit = 3_600;
+
+    for part in value[(split + 1)..].split(':') {
+        if unit == 0 {
+            return None;
+        }
+
+        offset += part.parse::<i64>().ok()? * unit;
+        unit /= 60;
+    }
+
+    if value[split..].starts_with('-') {
+        offset = -offset;
+    }
+
+    Some((micros, offset))
+}
+
+/// The native encoding of a `TIMETZ`: the time in UTC, in microseconds since midnight,
+/// in the upper 40 bits, and the offset in the lower 24 bits, as seconds west of
+/// Greenwich plus 24 hours so it's never negative.
+pub(crate) fn encode_timetz(micros: i64, offset: i64) -> i64 {
+    let utc = (micros - offset * 1_000_000).rem_euclid(MICROSECONDS_PER_DAY);
+
+    (utc << 24) | (SECONDS_PER_DAY - offset)
+}
+
+/// Round microseconds to a number of fractional digits, as a `TIME(p)` or
+/// `TIMESTAMP(p)` would store them; without a precision, microseconds are kept.
+pub(crate) fn round_to_precision(micros: i64, precision: Option<u16>) -> i64 {
+    let digits = match precision {
+        Some(digits) if digits < 6 => digits as u32,
+        _ => return micros,
+    };
+
+    let unit = 10_i64.pow(6 - digits);
+
+    (micros + unit / 2).div_euclid(unit) * unit
+}
+
+/// Parse a date, as Vertica writes it with `datestyle` set to `iso` (e.g. `2020-06-01`,
+/// `0044-03-15 BC` or `infinity`), into days since 2000-01-01.
+pub(crate) fn parse_date(value: &str) -> Option<i64> {
+    match infinity(value) {
+        Some(sentinel) => Some(sentinel),
+        None => {
+            let (value, bc) = split_era(value);
+            days_since_2000(value, bc)
+        }
+    }
+}
+
+/// Parse a timestamp, as Vertica writes it with `datestyle` set to `iso` (e.g.
+/// `2020-06-01 12:34:56.5`, `0044-03-15 12:00:00 BC` or `infinity`), into microseconds
+/// since 2000-01-01 00:00. A `timestamptz`'s offset is taken off, to give UTC.
+pub(crate) fn parse_timestamp(value: &str) -> Option<i64> {
+    if let Some(sentinel) = infinity(value) {
+        return Some(sentinel);
+    }
+
+    let (value, bc) = split_era(value);
+    let mut parts = value.splitn(2, ' ');
+
+    let days = days_since_2000(parts.next()?, bc)?;
+
+    let time = parts.next().unwrap_or("00:00:00").trim();
+    let micros = match time.find(&['+', '-'][..]) {
+        Some(_) => {
+            let (micros, offset) = parse_timetz(time)?;
+            micros.checked_sub(offset.checked_mul(1_000_000)?)?
+        }
+        None => parse_time(time)?,
+    };
+
+    days.checked_mul(MICROSECONDS_PER_DAY)?.checked_add(micros)
+}
+
+/// Whether a value is one of the infinity sentinels, which mustn't be rounded.
+pub(crate) fn is_infinite(value: i64) -> bool {
+    value == INFINITY || value == NEGATIVE_INFINITY
+}
+
+fn infinity(value: &str) -> Option<i64> {
+    match value.trim().to_lowercase().as_str() {
+        "infinity" | "+infinity" => Some(INFINITY),
+        "-infinity" => Some(NEGATIVE_INFINITY),
+        _ => None,
+    }
+}
+
+/// Take a trailing ` BC` off a value, saying whether it was there.
+fn split_era(value: &str) -> (&str, bool) {
+    let value = value.trim();
+
+    if value.len() > 3 && value[(value.len() - 3)..].eq_ignore_ascii_case(" bc") {
+        (value[..(value.len() - 3)].trim_end(), true)
+    } else {
+        (value, false)
+    }
+}
+
+/// Days from 2000-01-01 to a `YYYY-MM-DD` date. There's no year 0, so 1 BC is year 0
+/// when counting, 2 BC is year -1, and so on. This is done by hand, rather than with
+/// chrono, which stops short of the years Vertica allows.
+fn days_since_2000(value: &str, bc: bool) -> Option<i64> {
+    let parts: Vec<&str> = value.split('-').collect();
+
+    if parts.len() != 3 {
+        return None;
+    }
+
+    let year = parts[0].parse::<i64>().ok()?;
+    let year = if bc { 1 - year } else { year };
+    let month = parts[1].parse::<i64>().ok()?;
+    let day = parts[2].parse::<i64>().ok()?;
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+
+    if day < 1 || day > days_in_month {
+        return None;
+    }
+
+    // count from 0000-03-01, so leap days fall at the end of each year
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    Some(era * 146_097 + day_of_era - 730_425)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+
+    /// A native value written as little-endian hex pairs, as in the `.feature` files.
+    fn native(string: &str) -> i64 {
+        let bytes: Vec<u8> = string
+            .split_whitespace()
+            .map(|pair| u8::from_str_radix(pair, 16).unwrap())
+            .collect();
+
+        i64::from_le_bytes(bytes[..].try_into().unwrap())
+    }
+
+    // timetz.feature's vectors
+    #[test]
+    fn timetz() {
+        let cases = [
+            ("15:12:34-05", "d0 97 01 80 f0 79 f0 10"),
+            ("09:30:00+05:30", "28 04 01 00 90 4e 5a 03"),
+            ("00:00:00-03:30", "b8 82 01 00 be 04 ef 02"),
+            ("12:00:00.5+14", "a0 8c 00 20 b9 b7 70 12"),
+            ("23:59:59.999999+00", "80 51 01 ff 5f d7 1d 14"),
+        ];
+
+        for (value, expected) in &cases {
+            let (micros, offset) = parse_timetz(value).unwrap();
+            assert_eq!(encode_timetz(micros, offset), native(expected), "{}", value);
+        }
+    }
+
+    #[test]
+    fn timetz_offsets() {
+        assert_eq!(
+            parse_timetz("09:30:00+05:30"),
+            Some((34_200_000_000, 19_800))
+        );
+        assert_eq!(parse_timetz("00:00:00-03:30"), Some((0, -12_600)));
+        assert_eq!(
+            parse_timetz("01:02:03.5-00:30:15"),
+            Some((3_723_500_000, -1_815))
+        );
+        assert_eq!(parse_timetz("15:12:34"), None);
+        assert_eq!(parse_timetz("15:12:34+05:30:00:00"), None);
+    }
+
+    #[test]
+    fn rounding() {
+        assert_eq!(round_to_precision(123_456, None), 123_456);
+        assert_eq!(round_to_precision(123_456, Some(6)), 123_456);
+        assert_eq!(round_to_precision(123_456, Some(9)), 123_456);
+        assert_eq!(round_to_precision(123_456, Some(3)), 123_000);
+        assert_eq!(round_to_precision(123_500, Some(3)), 124_000);
+        assert_eq!(round_to_precision(999_999, Some(2)), 1_000_000);
+        assert_eq!(round_to_precision(1_499_999, Some(0)), 1_000_000);
+        assert_eq!(round_to_precision(1_500_000, Some(0)), 2_000_000);
+        assert_eq!(round_to_precision(-1_400, Some(3)), -1_000);
+        assert_eq!(round_to_precision(-1_600, Some(3)), -2_000);
+    }
+
+    #[test]
+    fn times() {
+        assert_eq!(parse_time("12:34:56"), Some(45_296_000_000));
+        assert_eq!(parse_time("12:34:56.789"), Some(45_296_789_000));
+        assert_eq!(parse_time("00:00:00.000001"), Some(1));
+        assert_eq!(parse_time("12:34"), None);
+    }
+
+    // dates.feature's vectors
+    #[test]
+    fn dates_and_timestamps() {
+        let dates = [
+            ("2000-01-01", "00 00 00 00 00 00 00 00"),
+            ("1999-12-31", "ff ff ff ff ff ff ff ff"),
+            ("0001-12-31 BC", "f8 db f4 ff ff ff ff ff"),
+            ("4713-01-01 BC", "cd 97 da ff ff ff ff ff"),
+            ("infinity", "ff ff ff ff ff ff ff 7f"),
+            ("-infinity", "01 00 00 00 00 00 00 80"),
+        ];
+
+        for (value, expected) in &dates {
+            assert_eq!(parse_date(value), Some(native(expected)), "{}", value);
+        }
+
+        let timestamps = [
+            ("2020-06-01 12:34:56.5", "20 1d 85 ca 03 4a 02 00"),
+            ("2024-02-29 00:00:00", "00 60 8c b5 78 b5 02 00"),
+            ("0001-01-01 00:00:00 BC", "00 20 b1 1b 3d c6 1f ff"),
+            ("294276-12-31 23:59:59", "c0 5d a3 b3 5b ff ff 7f"),
+            ("infinity", "ff ff ff ff ff ff ff 7f"),
+            ("-infinity", "01 00 00 00 00 00 00 80"),
+        ];
+
+        for (value, expected) in &timestamps {
+            assert_eq!(parse_timestamp(value), Some(native(expected)), "{}", value);
+        }
+    }
+
+    #[test]
+    fn timestamps_with_offsets() {
+        let utc = parse_timestamp("2020-06-01 12:34:56.5");
+
+        assert_eq!(parse_timestamp("2020-06-01 07:34:56.5-05"), utc);
+        assert_eq!(parse_timestamp("2020-06-01 18:04:56.5+05:30"), utc);
+        assert_eq!(parse_timestamp("2020-06-01 12:34:56.5+00"), utc);
+    }
+
+    #[test]
+    fn leap_years() {
+        assert_eq!(days_since_2000("2000-02-29", false), Some(59));
+        assert_eq!(days_since_2000("2024-02-29", false), Some(8_825));
+        assert_eq!(days_since_2000("1900-02-29", false), None);
+        assert_eq!(days_since_2000("2023-02-29", false), None);
+
+        // 1 BC, 5 BC and 401 BC are years 0, -4 and -400, which are leap years
+        assert_eq!(days_since_2000("0001-01-01", true), Some(-730_485));
+        assert_eq!(days_since_2000("0001-02-29", true), Some(-730_426));
+        assert_eq!(days_since_2000("0005-02-29", true), Some(-731_887));
+        assert_eq!(
+            days_since_2000("0401-03-01", true).unwrap()
+                - days_since_2000("0401-02-28", true).unwrap(),
+            2
+        );
+        assert_eq!(days_since_2000("0002-02-29", true), None);
+        assert_eq!(days_since_2000("0101-02-29", true), None);
+    }
+
+    #[test]
+    fn invalid_dates() {
+        assert_eq!(parse_date("2020-13-01"), None);
+        assert_eq!(parse_date("2020-04-31"), None);
+        assert_eq!(parse_date("2020-06-00"), None);
+        assert_eq!(parse_date("2020-06"), None);
+        assert_eq!(parse_timestamp("2020-06-01 12:34"), None);
+    }
+}
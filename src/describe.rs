@@ -0,0 +1,64 @@
+use std::ptr;
+
+use odbc::ffi;
+
+/// A result-set column, as described by `SQLDescribeCol`.
+#[derive(Debug)]
+pub(crate) struct ColumnDescription {
+    pub(crate) name: String,
+    /// The ODBC SQL type code. It's kept as a number, since drivers report codes (such
+    /// as the `SQL_INTERVAL_*` ones) that `ffi::SqlDataType` has no variant for.
+    pub(crate) data_type: i16,
+    pub(crate) column_size: ffi::SQLULEN,
+    pub(crate) decimal_digits: i16,
+}
+
+/// Describe a column of a statement's result set. The odbc crate's `describe_col`
+/// has the driver write the type code straight into an `ffi::SqlDataType`, which is
+/// undefined behaviour for codes that enum doesn't list, so `SQLDescribeCol` is
+/// called here with an `i16` in its place.
+///
+/// `stmt` must be the handle of an executed statement. Returns `Err` if the driver
+/// reports an error.
+pub(crate) fn describe_column(stmt: ffi::SQLHSTMT, column: u16) -> Result<ColumnDescription, ()> {
+    let mut name = vec![0_u8; 256];
+
+    loop {
+        let mut name_length: ffi::SQLSMALLINT = 0;
+        let mut data_type: ffi::SQLSMALLINT = 0;
+        let mut column_size: ffi::SQLULEN = 0;
+        let mut decimal_digits: ffi::SQLSMALLINT = 0;
+
+        let result = unsafe {
+            ffi::SQLDescribeCol(
+                stmt,
+                column,
+                name.as_mut_ptr(),
+                name.len() as ffi::SQLSMALLINT,
+                &mut name_length as *mut ffi::SQLSMALLINT,
+                &mut data_type as *mut ffi::SQLSMALLINT as *mut ffi::SqlDataType,
+                &mut column_size as *mut ffi::SQLULEN,
+                &mut decimal_digits as *mut ffi::SQLSMALLINT,
+                ptr::null_mut(),
+            )
+        };
+
+        match result {
+            ffi::SQL_SUCCESS | ffi::SQL_SUCCESS_WITH_INFO => {}
+            _ => return Err(()),
+        }
+
+        // the name was truncated; there's room for the terminating nul next time
+        if name_length as usize >= name.len() {
+            name.resize(name_length as usize + 1, 0);
+            continue;
+        }
+
+        return Ok(ColumnDescription {
+            name: String::from_utf8_lossy(&name[..name_length as usize]).into_owned(),
+            data_type,
+            column_size,
+            decimal_digits,
+        });
+    }
+}
@@ -2,15 +2,117 @@ use core::fmt;
 use std::error::Error;
 use std::fmt::{Display, Formatter};
 
+#[allow(clippy::enum_variant_names)]
 #[derive(Debug)]
 pub enum Errors {
-    TableNotFoundError,
+    TableNotFoundError(String),
+    AmbiguousTableError(String, Vec<String>),
+    UnsupportedTypeError(Vec<String>),
+    UnknownColumnsError(Vec<String>),
+    DuplicateColumnError(String),
+    InvalidParameterError(String),
+    MissingParameterError(String),
+    UnusedParameterError(String),
+    NoTablesError(String),
+    OutputExistsError(String),
+    InvalidJobError(String),
+    InvalidSampleError(String),
+    NoPrimaryKeyError(String),
+    NotPartitionedError(String),
+    InvalidValueError(String, String),
+    OutOfRangeError(String, String),
+    ReadError(String),
+    InvalidCastError(String),
+    ValueTooLongError(String, usize, usize),
+    InvalidTextError(String, String),
+    AmbiguousTimestampError(Vec<String>),
+    UnquotableValueError(String),
 }
 
 impl Error for Errors {}
 
 impl Display for Errors {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        write!(f, "error: {:?}", self)
+        match self {
+            Errors::TableNotFoundError(table) => write!(f, "error: table {} not found", table),
+            Errors::AmbiguousTableError(table, candidates) => write!(
+                f,
+                "error: table name {} is ambiguous; it matches {}; qualify it with a schema",
+                table,
+                candidates.join(", ")
+            ),
+            Errors::UnsupportedTypeError(columns) => write!(
+                f,
+                "error: unsupported data type for column(s): {}; use `--unsupported-types skip` or `cast` to extract the rest",
+                columns.join(", ")
+            ),
+            Errors::UnknownColumnsError(columns) => {
+                write!(f, "error: no such column(s): {}", columns.join(", "))
+            }
+            Errors::DuplicateColumnError(column) => {
+                write!(f, "error: column {} is selected more than once", column)
+            }
+            Errors::InvalidParameterError(param) => {
+                write!(
+                    f,
+                    "error: parameter [{}] must be given as name=value",
+                    param
+                )
+            }
+            Errors::MissingParameterError(name) => {
+                write!(f, "error: no value given for parameter :{}", name)
+            }
+            Errors::UnusedParameterError(name) => {
+                write!(f, "error: parameter {} is not used in the filter", name)
+            }
+            Errors::NoTablesError(schema) => {
+                write!(f, "error: no tables to extract in schema {}", schema)
+            }
+            Errors::OutputExistsError(path) => {
+                write!(f, "error: file [{}] exists; use `-f` to force", path)
+            }
+            Errors::InvalidJobError(msg) => write!(f, "error: invalid job file: {}", msg),
+            Errors::InvalidSampleError(msg) => write!(f, "error: invalid sample: {}", msg),
+            Errors::NoPrimaryKeyError(table) => {
+                write!(f, "error: {} has no primary key to order by", table)
+            }
+            Errors::NotPartitionedError(table) => write!(f, "error: {} is not partitioned", table),
+            Errors::InvalidValueError(column, value) => {
+                write!(
+                    f,
+                    "error: can't convert value [{}] of column {}",
+                    value, column
+                )
+            }
+            Errors::OutOfRangeError(column, value) => write!(
+                f,
+                "error: value [{}] of column {} is out of range",
+                value, column
+            ),
+            Errors::ReadError(column) => write!(f, "error: couldn't read column {}", column),
+            Errors::InvalidCastError(cast) => {
+                write!(f, "error: invalid cast [{}]; expected COLUMN=TYPE", cast)
+            }
+            Errors::ValueTooLongError(column, length, width) => write!(
+                f,
+                "error: a value of column {} is {} bytes, longer than its width of {}",
+                column, length, width
+            ),
+            Errors::InvalidTextError(column, encoding) => write!(
+                f,
+                "error: a value of column {} isn't valid {}; use `--encoding` to name its encoding, or `--encoding raw`",
+                column, encoding
+            ),
+            Errors::AmbiguousTimestampError(columns) => write!(
+                f,
+                "error: can't tell whether column(s) {} are timestamp or timestamptz, so `--timezone` can't be used; convert them in the query instead",
+                columns.join(", ")
+            ),
+            Errors::UnquotableValueError(column) => write!(
+                f,
+                "error: a value of column {} holds the delimiter or a line break, or is the null marker, so it can't be written with `--quoting never`",
+                column
+            ),
+        }
     }
 }
@@ -0,0 +1,78 @@
+use crate::csv_output::CsvOptions;
+use crate::output_format::OutputFormat;
+use crate::sample::Sample;
+use crate::text_encoding::TextEncoding;
+use crate::unsupported_types::UnsupportedTypes;
+
+/// How to order the extracted rows, so that extracting the same data twice gives
+/// byte-for-byte identical files.
+#[derive(Debug, Clone, PartialEq)]
+pub enum OrderBy {
+    /// An `order by` list, e.g. `event_time, event_id desc`.
+    Columns(String),
+    /// The table's primary key columns.
+    PrimaryKey,
+}
+
+impl OrderBy {
+    /// Parse an `--order-by` value; `primary key` means the table's primary key, and
+    /// anything else is an `order by` list.
+    pub fn parse(string: &str) -> OrderBy {
+        let words: Vec<&str> = string.split_whitespace().collect();
+
+        match words.as_slice() {
+            [primary, key]
+                if primary.eq_ignore_ascii_case("primary") && key.eq_ignore_ascii_case("key") =>
+            {
+                OrderBy::PrimaryKey
+            }
+            _ => OrderBy::Columns(string.trim().to_string()),
+        }
+    }
+}
+
+/// Optional settings that control what gets extracted, and how.
+#[derive(Debug, Clone, Default)]
+pub struct ExtractOptions {
+    /// The columns to extract, in the order they should be written; all of them if `None`.
+    pub columns: Option<Vec<String>>,
+    /// A predicate that rows must satisfy, which may refer to `params` as `:name`.
+    pub filter: Option<String>,
+    /// Values for the filter's `:name` placeholders; they're bound as ODBC parameters.
+    pub params: Vec<(String, String)>,
+    /// Only extract rows in these partitions, given by partition key.
+    pub partitions: Option<Vec<String>>,
+    pub order_by: Option<OrderBy>,
+    /// The maximum number of rows to extract.
+    pub limit: Option<usize>,
+    /// Extract a random sample of the rows, rather than all of them.
+    pub sample: Option<Sample>,
+    /// Seed for the sampling, so the same rows are picked each time (as long as
+    /// Vertica returns them in the same order).
+    pub seed: Option<u64>,
+    /// The time zone plain `timestamp` values are in; they're converted to UTC. Without
+    /// one, they're written as they are.
+    pub timezone: Option<String>,
+    /// Columns to have Vertica cast to another type, as `(column, type)`, so the output
+    /// matches the table it'll be loaded into.
+    pub casts: Vec<(String, String)>,
+    /// What to do with columns of types that can't be extracted.
+    pub unsupported_types: UnsupportedTypes,
+    /// The encoding of character data.
+    pub encoding: TextEncoding,
+    /// The kind of file to write.
+    pub format: OutputFormat,
+    /// How to write CSV, when `format` is `Csv`.
+    pub csv: CsvOptions,
+}
+
+impl ExtractOptions {
+    /// Parse a comma-separated list, such as the one given to `--columns`.
+    pub fn parse_list(string: &str) -> Vec<String> {
+        string
+            .split(',')
+            .map(|column| column.trim().to_string())
+            .filter(|column| !column.is_empty())
+            .collect()
+    }
+}
@@ -0,0 +1,10 @@
+use std::error::Error;
+use std::path::PathBuf;
+
+/// What happened with one of several extractions, e.g. one table of a schema.
+#[derive(Debug)]
+pub struct ExtractOutcome {
+    pub name: String,
+    pub output_path: PathBuf,
+    pub result: Result<(), Box<dyn Error>>,
+}
@@ -0,0 +1,22 @@
+use regex::Regex;
+
+/// Does `name` match the shell-style `pattern`? `*` matches any run of characters and
+/// `?` any single character; the comparison ignores case, as Vertica identifiers do.
+pub(crate) fn glob_matches(pattern: &str, name: &str) -> bool {
+    let mut regex = String::from("(?i)^");
+
+    for c in pattern.chars() {
+        match c {
+            '*' => regex.push_str(".*"),
+            '?' => regex.push('.'),
+            _ => regex.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+
+    regex.push('$');
+
+    match Regex::new(&regex) {
+        Ok(regex) => regex.is_match(name),
+        Err(_) => false,
+    }
+}
@@ -0,0 +1,253 @@
+/// A field of an interval qualifier, such as the `day` and `second` in
+/// `interval day to second`, in order from most to least significant.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub enum IntervalField {
+    Year,
+    Month,
+    Day,
+    Hour,
+    Minute,
+    Second,
+}
+
+impl IntervalField {
+    fn from_string(string: &str) -> Option<IntervalField> {
+        match string {
+            "year" => Some(IntervalField::Year),
+            "month" => Some(IntervalField::Month),
+            "day" => Some(IntervalField::Day),
+            "hour" => Some(IntervalField::Hour),
+            "minute" => Some(IntervalField::Minute),
+            "second" => Some(IntervalField::Second),
+            _ => None,
+        }
+    }
+
+    pub(crate) fn is_year_month(self) -> bool {
+        self == IntervalField::Year || self == IntervalField::Month
+    }
+}
+
+/// Parse the qualifier of an interval type, i.e. whatever follows `interval`, such as
+/// `day to second`, `hour` or nothing at all (which means `day to second`). Returns
+/// the leading and trailing fields, or `None` if the qualifier isn't valid.
+pub(crate) fn parse_qualifier(qualifier: &str) -> Option<(IntervalField, IntervalField)> {
+    let words: Vec<&str> = qualifier.split_whitespace().collect();
+
+    let (leading, trailing) = match words.as_slice() {
+        [] => (IntervalField::Day, IntervalField::Second),
+        [field] => {
+            let field = IntervalField::from_string(field)?;
+            (field, field)
+        }
+        [leading, "to", trailing] => (
+            IntervalField::from_string(leading)?,
+            IntervalField::from_string(trailing)?,
+        ),
+        _ => return None,
+    };
+
+    if leading > trailing || leading.is_year_month() != trailing.is_year_month() {
+        return None;
+    }
+
+    Some((leading, trailing))
+}
+
+/// Convert a day-time interval, as Vertica writes it with `intervalstyle` set to
+/// `plain` (e.g. `-1 02:03:04.5`), into microseconds. Which unit the first number is
+/// in depends on the type's leading field.
+pub(crate) fn day_time_to_microseconds(value: &str, leading: IntervalField) -> Option<i64> {
+    let (negative, value) = split_sign(value);
+
+    // Vertica leaves the day count out when it's zero, e.g. `01:00` for an hour
+    let has_days = match value.split_whitespace().next() {
+        Some(first) => !first.contains(':'),
+        None => false,
+    };
+
+    let (days, rest) = if leading == IntervalField::Day && has_days {
+        let mut parts = value.splitn(2, ' ');
+        let days = parts.next()?.parse::<i64>().ok()?;
+        (days, parts.next().unwrap_or("").trim())
+    } else {
+        (0, value)
+    };
+
+    // the units of the colon-separated parts, in order
+    let units: &[i64] = match leading {
+        IntervalField::Day | IntervalField::Hour => &[3_600_000_000, 60_000_000, 1_000_000],
+        IntervalField::Minute => &[60_000_000, 1_000_000],
+        IntervalField::Second => &[1_000_000],
+        _ => return None,
+    };
+
+    let mut total = days.checked_mul(86_400_000_000)?;
+
+    if !rest.is_empty() {
+        let parts: Vec<&str> = rest.split(':').collect();
+
+        if parts.len() > units.len() {
+            return None;
+        }
+
+        for (index, part) in parts.iter().enumerate() {
+            let micros = if index == units.len() - 1 {
+                seconds_to_microseconds(part)?
+            } else {
+                part.parse::<i64>().ok()?.checked_mul(units[index])?
+            };
+
+            total = total.checked_add(micros)?;
+        }
+    }
+
+    Some(if negative { -total } else { total })
+}
+
+/// Convert a year-month interval, as Vertica writes it (e.g. `1-2`, or just `14` for
+/// `interval month`), into months.
+pub(crate) fn year_month_to_months(value: &str, leading: IntervalField) -> Option<i64> {
+    let (negative, value) = split_sign(value);
+
+    let months = match value.find('-') {
+        Some(index) => {
+            let years = value[..index].parse::<i64>().ok()?;
+            let months = value[(index + 1)..].parse::<i64>().ok()?;
+            years.checked_mul(12)?.checked_add(months)?
+        }
+        None if leading == IntervalField::Year => value.parse::<i64>().ok()?.checked_mul(12)?,
+        None => value.parse::<i64>().ok()?,
+    };
+
+    Some(if negative { -months } else { months })
+}
+
+fn split_sign(value: &str) -> (bool, &str) {
+    let value = value.trim();
+
+    if let Some(value) = value.strip_prefix('-') {
+        (true, value.trim_start())
+    } else if let Some(value) = value.strip_prefix('+') {
+        (false, value.trim_start())
+    } else {
+        (false, value)
+    }
+}
+
+/// `SS` or `SS.ffffff`, in microseconds; digits beyond microseconds are dropped.
+pub(crate) fn seconds_to_microseconds(value: &str) -> Option<i64> {
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next()?.parse::<i64>().ok()?;
+
+    let fraction = match parts.next() {
+        None => 0,
+        Some(digits) => {
+            let digits: String = digits.chars().chain("000000".chars()).take(6).collect();
+            digits.parse::<i64>().ok()?
+        }
+    };
+
+    whole.checked_mul(1_000_000)?.checked_add(fraction)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryInto;
+
+    use super::*;
+    use IntervalField::*;
+
+    /// A native value written as little-endian hex pairs, as in the `.feature` files.
+    fn native(string: &str) -> i64 {
+        let bytes: Vec<u8> = string
+            .split_whitespace()
+            .map(|pair| u8::from_str_radix(pair, 16).unwrap())
+            .collect();
+
+        i64::from_le_bytes(bytes[..].try_into().unwrap())
+    }
+
+    #[test]
+    fn qualifiers() {
+        assert_eq!(parse_qualifier(""), Some((Day, Second)));
+        assert_eq!(parse_qualifier("hour"), Some((Hour, Hour)));
+        assert_eq!(parse_qualifier("hour to minute"), Some((Hour, Minute)));
+        assert_eq!(parse_qualifier("year to month"), Some((Year, Month)));
+        assert_eq!(parse_qualifier("second to minute"), None);
+        assert_eq!(parse_qualifier("year to day"), None);
+        assert_eq!(parse_qualifier("fortnight"), None);
+    }
+
+    // the values intervals.feature stores, as Vertica reads them back
+    #[test]
+    fn day_time_intervals() {
+        let cases = [
+            ("01:00", Day, "00 a4 93 d6 00 00 00 00"),
+            ("00:00:01.5", Day, "60 e3 16 00 00 00 00 00"),
+            ("-01:00", Day, "00 5c 6c 29 ff ff ff ff"),
+            ("00:00", Day, "00 00 00 00 00 00 00 00"),
+            ("1 02:03", Day, "00 3d b9 d5 15 00 00 00"),
+            ("-1 02:00", Day, "00 58 01 35 ea ff ff ff"),
+            ("01:00", Hour, "00 a4 93 d6 00 00 00 00"),
+            ("01:30", Minute, "80 4a 5d 05 00 00 00 00"),
+        ];
+
+        for (value, leading, expected) in &cases {
+            assert_eq!(
+                day_time_to_microseconds(value, *leading),
+                Some(native(expected)),
+                "{} ({:?})",
+                value,
+                leading
+            );
+        }
+    }
+
+    #[test]
+    fn leading_fields() {
+        assert_eq!(day_time_to_microseconds("2", Day), Some(172_800_000_000));
+        assert_eq!(
+            day_time_to_microseconds("26:00", Hour),
+            Some(93_600_000_000)
+        );
+        assert_eq!(day_time_to_microseconds("26", Hour), Some(93_600_000_000));
+        assert_eq!(
+            day_time_to_microseconds("90:30", Minute),
+            Some(5_430_000_000)
+        );
+        assert_eq!(day_time_to_microseconds("90.25", Second), Some(90_250_000));
+        assert_eq!(day_time_to_microseconds("-0.000001", Second), Some(-1));
+    }
+
+    #[test]
+    fn invalid_day_time_intervals() {
+        assert_eq!(day_time_to_microseconds("1:00:00:00", Hour), None);
+        assert_eq!(day_time_to_microseconds("1 00:00", Hour), None);
+        assert_eq!(day_time_to_microseconds("1-2", Day), None);
+        assert_eq!(day_time_to_microseconds("01:00", Year), None);
+    }
+
+    #[test]
+    fn year_month_intervals() {
+        assert_eq!(
+            year_month_to_months("1-2", Year),
+            Some(native("0e 00 00 00 00 00 00 00"))
+        );
+        assert_eq!(
+            year_month_to_months("-1-2", Year),
+            Some(native("f2 ff ff ff ff ff ff ff"))
+        );
+        assert_eq!(year_month_to_months("3", Year), Some(36));
+        assert_eq!(year_month_to_months("14", Month), Some(14));
+        assert_eq!(year_month_to_months("-14", Month), Some(-14));
+        assert_eq!(year_month_to_months("x", Month), None);
+    }
+
+    #[test]
+    fn seconds() {
+        assert_eq!(seconds_to_microseconds("5"), Some(5_000_000));
+        assert_eq!(seconds_to_microseconds("5.5"), Some(5_500_000));
+        assert_eq!(seconds_to_microseconds("0.1234567"), Some(123_456));
+    }
+}
@@ -0,0 +1,276 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use odbc::create_environment_v3;
+use serde::Deserialize;
+
+use crate::connection_options::ConnectionOptions;
+use crate::csv_output::CsvOptions;
+use crate::errors::Errors;
+use crate::extract_options::{ExtractOptions, OrderBy};
+use crate::extract_outcome::ExtractOutcome;
+use crate::extract_with_connection;
+use crate::output_format::OutputFormat;
+use crate::sample::Sample;
+use crate::source::Source;
+use crate::text_encoding::TextEncoding;
+use crate::unsupported_types::UnsupportedTypes;
+
+/// A set of extractions, read from a TOML job file, that share one connection.
+///
+/// ```toml
+/// [connection]
+/// server = "localhost"
+/// database = "mydb"
+///
+/// [[extract]]
+/// table = "public.events"
+/// columns = ["event_id", "event_time"]
+/// where = "event_time >= :start"
+/// params = { start = "2020-06-01" }
+/// output = "events.bin"
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    pub connection: JobConnection,
+    /// Overwrite output files that already exist.
+    #[serde(default)]
+    pub force: bool,
+    #[serde(rename = "extract", default)]
+    pub extractions: Vec<JobExtraction>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobConnection {
+    #[serde(default = "default_server")]
+    pub server: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub database: String,
+    #[serde(default = "default_username")]
+    pub username: String,
+    pub password: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct JobExtraction {
+    /// What to call this extraction when reporting on it; defaults to the table name.
+    pub name: Option<String>,
+    pub table: Option<String>,
+    pub query: Option<String>,
+    pub query_file: Option<PathBuf>,
+    pub columns: Option<Vec<String>>,
+    #[serde(rename = "where")]
+    pub filter: Option<String>,
+    #[serde(default)]
+    pub params: BTreeMap<String, toml::Value>,
+    pub partitions: Option<Vec<String>>,
+    /// An `order by` list, or `"primary key"` to order by the table's primary key.
+    pub order_by: Option<String>,
+    pub limit: Option<usize>,
+    pub sample_percent: Option<f64>,
+    pub sample_rows: Option<usize>,
+    pub seed: Option<u64>,
+    /// The time zone plain `timestamp` values are in, e.g. `"America/New_York"`.
+    pub timezone: Option<String>,
+    /// Columns to cast, e.g. `{ amount = "float" }`.
+    #[serde(default)]
+    pub casts: BTreeMap<String, String>,
+    /// `"fail"`, `"skip"` or `"cast"`.
+    #[serde(default)]
+    pub unsupported_types: UnsupportedTypes,
+    /// `"raw"`, or the encoding of character data, e.g. `"latin1"`.
+    pub encoding: Option<String>,
+    pub output: PathBuf,
+    /// `"native"` or `"csv"`.
+    #[serde(default)]
+    pub format: OutputFormat,
+    /// How to write CSV, as an `[extract.csv]` table.
+    #[serde(default)]
+    pub csv: CsvOptions,
+}
+
+fn default_server() -> String {
+    "localhost".to_string()
+}
+
+fn default_port() -> u16 {
+    5433
+}
+
+fn default_username() -> String {
+    "dbadmin".to_string()
+}
+
+impl Job {
+    /// Read a job file. Relative `output` and `query_file` paths are taken to be
+    /// relative to the job file, so a job can be run from anywhere.
+    pub fn load(path: &Path) -> Result<Job, Box<dyn Error>> {
+        let text = fs::read_to_string(path)?;
+
+        let mut job: Job = toml::from_str(&text)
+            .map_err(|e| Errors::InvalidJobError(format!("{}: {}", path.display(), e)))?;
+
+        let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+
+        for (index, extraction) in job.extractions.iter_mut().enumerate() {
+            let sources = [
+                extraction.table.is_some(),
+                extraction.query.is_some(),
+                extraction.query_file.is_some(),
+            ];
+
+            if sources.iter().filter(|s| **s).count() != 1 {
+                return Err(Box::new(Errors::InvalidJobError(format!(
+                    "extract #{} must have exactly one of table, query or query_file",
+                    index + 1
+                ))));
+            }
+
+            if extraction.sample_percent.is_some() && extraction.sample_rows.is_some() {
+                return Err(Box::new(Errors::InvalidJobError(format!(
+                    "extract #{} can't have both sample_percent and sample_rows",
+                    index + 1
+                ))));
+            }
+
+            if let Err(msg) = extraction.csv.validate() {
+                return Err(Box::new(Errors::InvalidJobError(format!(
+                    "extract #{} has invalid csv options: {}",
+                    index + 1,
+                    msg
+                ))));
+            }
+
+            if let Some(encoding) = &extraction.encoding {
+                if TextEncoding::parse(encoding).is_none() {
+                    return Err(Box::new(Errors::InvalidJobError(format!(
+                        "extract #{} has an unknown encoding: {}",
+                        index + 1,
+                        encoding
+                    ))));
+                }
+            }
+
+            extraction.output = base_dir.join(&extraction.output);
+            extraction.query_file = extraction.query_file.as_ref().map(|f| base_dir.join(f));
+        }
+
+        if job.extractions.is_empty() {
+            return Err(Box::new(Errors::InvalidJobError(format!(
+                "{}: no [[extract]] sections",
+                path.display()
+            ))));
+        }
+
+        Ok(job)
+    }
+
+    pub fn connection_options(&self) -> ConnectionOptions {
+        ConnectionOptions {
+            server: self.connection.server.clone(),
+            port: self.connection.port,
+            database: self.connection.database.clone(),
+            username: self.connection.username.clone(),
+            password: self.connection.password.clone(),
+        }
+    }
+}
+
+impl JobExtraction {
+    fn name(&self, index: usize) -> String {
+        match (&self.name, &self.table) {
+            (Some(name), _) => name.clone(),
+            (None, Some(table)) => table.clone(),
+            (None, None) => format!("extract #{}", index + 1),
+        }
+    }
+
+    fn source(&self) -> Result<Source, Box<dyn Error>> {
+        match (&self.table, &self.query, &self.query_file) {
+            (Some(table), _, _) => Ok(Source::Table(table.clone())),
+            (_, Some(query), _) => Ok(Source::query(query)),
+            (_, _, Some(query_file)) => Ok(Source::query(&fs::read_to_string(query_file)?)),
+            _ => unreachable!("checked when the job was loaded"),
+        }
+    }
+
+    fn options(&self) -> ExtractOptions {
+        let params = self
+            .params
+            .iter()
+            .map(|(name, value)| {
+                let value = match value {
+                    toml::Value::String(value) => value.clone(),
+                    value => value.to_string(),
+                };
+
+                (name.clone(), value)
+            })
+            .collect();
+
+        let sample = match (self.sample_percent, self.sample_rows) {
+            (Some(percent), _) => Some(Sample::Percent(percent)),
+            (None, Some(rows)) => Some(Sample::Rows(rows)),
+            (None, None) => None,
+        };
+
+        ExtractOptions {
+            columns: self.columns.clone(),
+            filter: self.filter.clone(),
+            params,
+            partitions: self.partitions.clone(),
+            order_by: self.order_by.as_deref().map(OrderBy::parse),
+            limit: self.limit,
+            sample,
+            seed: self.seed,
+            timezone: self.timezone.clone(),
+            casts: self
+                .casts
+                .iter()
+                .map(|(column, type_name)| (column.clone(), type_name.clone()))
+                .collect(),
+            unsupported_types: self.unsupported_types,
+            // checked when the job was loaded
+            encoding: self
+                .encoding
+                .as_deref()
+                .and_then(TextEncoding::parse)
+                .unwrap_or_default(),
+            format: self.format,
+            csv: self.csv.clone(),
+        }
+    }
+}
+
+/// Run every extraction in a job, over a single connection. A failed extraction
+/// doesn't stop the others; each one's outcome is returned, in job file order.
+pub fn run_job(job: &Job, force: bool) -> Result<Vec<ExtractOutcome>, Box<dyn Error>> {
+    let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    let conn = env.connect_with_connection_string(&job.connection_options().dsn())?;
+
+    let mut outcomes: Vec<ExtractOutcome> = vec![];
+
+    for (index, extraction) in job.extractions.iter().enumerate() {
+        let result = if extraction.output.exists() && !(force || job.force) {
+            Err(Errors::OutputExistsError(extraction.output.display().to_string()).into())
+        } else {
+            extraction.source().and_then(|source| {
+                extract_with_connection(&conn, &source, &extraction.options(), &extraction.output)
+            })
+        };
+
+        outcomes.push(ExtractOutcome {
+            name: extraction.name(index),
+            output_path: extraction.output.clone(),
+            result,
+        });
+    }
+
+    Ok(outcomes)
+}
@@ -1,116 +1,300 @@
-use std::convert::TryInto;
 use std::error::Error;
 use std::fs::File;
 use std::io::Write;
 use std::path::Path;
-use std::str::FromStr;
 
-use chrono::{Local, NaiveDate, NaiveTime};
 use odbc::odbc_safe::AutocommitOn;
 use odbc::ResultSetState::{Data, NoData};
-use odbc::{create_environment_v3, Allocated, Cursor, SqlDate, SqlTime, SqlTimestamp};
-use odbc::{Connection, Statement};
+use odbc::{create_environment_v3, Allocated, Cursor};
+use odbc::{ffi, Connection, Handle, Statement};
 
+use crate::casts::cast_column_type;
+pub use crate::casts::parse_cast;
 use crate::column_type::ColumnType;
+pub use crate::connection_options::ConnectionOptions;
+use crate::csv_output::{create_csv_header, read_csv_row};
+pub use crate::csv_output::{BinaryEncoding, CsvOptions, Quoting};
+use crate::datetime::{
+    encode_timetz, is_infinite, parse_date, parse_time, parse_timestamp, parse_timetz,
+    round_to_precision,
+};
+use crate::describe::describe_column;
+use crate::errors::Errors;
+pub use crate::extract_options::{ExtractOptions, OrderBy};
+pub use crate::extract_outcome::ExtractOutcome;
+use crate::interval::{day_time_to_microseconds, year_month_to_months};
+pub use crate::job::{run_job, Job, JobConnection, JobExtraction};
+use crate::long_data::read_long_data;
+use crate::numeric::{encode_numeric, numeric_width};
+pub use crate::output_format::OutputFormat;
+use crate::parameters::bind_named_parameters;
+pub use crate::parameters::parse_param;
+use crate::partitions::create_partition_condition;
+pub use crate::partitions::{extract_partitions, list_partitions};
+pub use crate::sample::Sample;
+use crate::sample::{Placement, Sampler};
+pub use crate::schema::extract_schema;
+pub use crate::source::Source;
+use crate::spatial::{has_spatial_columns, write_copy_columns};
 use crate::sql_data_type::SqlDataType;
+use crate::table_name::{quote_identifier, quote_literal, TableName};
+pub use crate::text_encoding::TextEncoding;
+pub use crate::unsupported_types::UnsupportedTypes;
 
+mod casts;
 mod column_type;
+mod connection_options;
+mod csv_output;
+mod datetime;
+mod describe;
 mod errors;
+mod extract_options;
+mod extract_outcome;
+mod glob;
+mod interval;
+mod job;
+mod long_data;
+mod numeric;
+mod output_format;
+mod parameters;
+mod partitions;
+mod sample;
+mod schema;
+mod source;
+mod spatial;
 mod sql_data_type;
+mod table_name;
+mod text_encoding;
+mod unsupported_types;
 
 const GET_COLUMN_DEFINITIONS_QUERY: &str = include_str!("sql/get_column_definitions.sql");
+const FIND_TABLES_QUERY: &str = include_str!("sql/find_tables.sql");
+const GET_SEARCH_PATH_QUERY: &str = include_str!("sql/get_search_path.sql");
+const GET_CURRENT_USER_QUERY: &str = include_str!("sql/get_current_user.sql");
+const GET_PARTITION_EXPRESSION_QUERY: &str = include_str!("sql/get_partition_expression.sql");
+const GET_PARTITION_KEYS_QUERY: &str = include_str!("sql/get_partition_keys.sql");
+const GET_PRIMARY_KEY_QUERY: &str = include_str!("sql/get_primary_key.sql");
+const GET_SCHEMA_TABLES_QUERY: &str = include_str!("sql/get_schema_tables.sql");
+const SET_INTERVAL_STYLE_QUERY: &str = include_str!("sql/set_interval_style.sql");
+const SET_DATE_STYLE_QUERY: &str = include_str!("sql/set_date_style.sql");
+const SET_TIME_ZONE_QUERY: &str = include_str!("sql/set_time_zone.sql");
 const SELECT_ALL_QUERY: &str = include_str!("sql/select_all.sql");
+const SELECT_FROM_QUERY_QUERY: &str = include_str!("sql/select_from_query.sql");
 const FILE_HEADER: [u8; 11] = [
     0x4E, 0x41, 0x54, 0x49, 0x56, 0x45, 0x0A, 0xFF, 0x0D, 0x0A, 0x00,
 ];
 
 pub fn extract(
-    server: String,
-    port: u16,
-    database: String,
-    username: String,
-    password: Option<String>,
-    table: String,
-    limit: Option<usize>,
+    connection: &ConnectionOptions,
+    source: &Source,
+    options: &ExtractOptions,
     output_path: &Path,
 ) -> Result<(), Box<dyn Error>> {
-    let dsn = create_dsn(server, port, database, username, password).clone();
-
     let env = create_environment_v3().map_err(|e| e.unwrap())?;
-    let conn = env.connect_with_connection_string(&dsn)?;
-
-    let column_types: Vec<ColumnType> = get_column_types(&conn, &table)?;
+    let conn = env.connect_with_connection_string(&connection.dsn())?;
 
-    let column_definitions = generate_column_definitions(&column_types);
+    extract_with_connection(&conn, source, options, output_path)
+}
 
-    let limit = match limit {
+fn extract_with_connection<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    source: &Source,
+    options: &ExtractOptions,
+    output_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let limit = match options.limit {
         None => "".to_string(),
         Some(limit) => String::from(format!("limit {}", limit)),
     };
 
-    let query = SELECT_ALL_QUERY
-        .replace("XX_TABLE_NAME_XX", table.as_str())
-        .replace("XX_LIMIT_XX", limit.as_str());
+    // intervals are parsed from their text, so make sure it's in the expected style
+    execute(conn, SET_INTERVAL_STYLE_QUERY)?;
+    // and `timestamptz` values are read in UTC, whatever the session's default zone;
+    // the filter and partition expressions are evaluated in UTC too
+    execute(conn, SET_TIME_ZONE_QUERY)?;
+    // and dates as `YYYY-MM-DD`
+    execute(conn, SET_DATE_STYLE_QUERY)?;
+
+    let (filter, mut param_values) =
+        bind_named_parameters(options.filter.as_deref().unwrap_or(""), &options.params)?;
+
+    let mut conditions: Vec<String> = vec![];
+
+    if !filter.trim().is_empty() {
+        conditions.push(filter);
+    }
+
+    if let Some(sample) = &options.sample {
+        sample.validate()?;
+    }
+
+    // Vertica can sample a table itself, but not a query, and not reproducibly
+    let table_sample = match (source, options.sample, options.seed) {
+        (Source::Table(_), Some(Sample::Percent(percent)), None) => {
+            format!("tablesample({})", percent)
+        }
+        _ => "".to_string(),
+    };
+
+    let mut sampler = match options.sample {
+        Some(sample) if table_sample.is_empty() => Some(Sampler::new(sample, options.seed)),
+        _ => None,
+    };
+
+    let (query, column_types) = match source {
+        Source::Table(table) => {
+            let table = resolve_table(conn, table)?;
+            let mut column_types = get_column_types(conn, &table)?;
+
+            if let Some(partitions) = &options.partitions {
+                conditions.push(create_partition_condition(conn, &table, partitions.len())?);
+                param_values.extend(partitions.iter().cloned());
+            }
+
+            let select_list = match &options.columns {
+                None => "*".to_string(),
+                // without catalog entries, the columns are checked by Vertica instead
+                Some(columns) if column_types.is_empty() => {
+                    create_select_list(columns.iter().map(|c| c.as_str()))
+                }
+                Some(columns) => {
+                    column_types = select_columns(column_types, columns)?;
+                    create_select_list(column_types.iter().map(|c| c.name.as_str()))
+                }
+            };
+
+            let select_list = prepare_columns(
+                conn,
+                select_list,
+                &table.to_sql(),
+                &mut column_types,
+                options,
+            )?;
+
+            let order_by = match &options.order_by {
+                None => "".to_string(),
+                Some(OrderBy::Columns(columns)) => format!("order by {}", columns),
+                Some(OrderBy::PrimaryKey) => {
+                    let key = get_primary_key(conn, &table)?;
+                    format!(
+                        "order by {}",
+                        create_select_list(key.iter().map(|c| c.as_str()))
+                    )
+                }
+            };
+
+            let query = SELECT_ALL_QUERY
+                .replace("XX_COLUMNS_XX", select_list.as_str())
+                .replace("XX_TABLE_NAME_XX", table.to_sql().as_str())
+                .replace("XX_TABLESAMPLE_XX", table_sample.as_str())
+                .replace("XX_WHERE_XX", create_where(&conditions).as_str())
+                .replace("XX_ORDER_BY_XX", order_by.as_str())
+                .replace("XX_LIMIT_XX", limit.as_str());
+
+            (query, column_types)
+        }
+        Source::Query(query) => {
+            if options.partitions.is_some() {
+                return Err(Box::new(Errors::NotPartitionedError("a query".to_string())));
+            }
+
+            let select_list = match &options.columns {
+                None => "*".to_string(),
+                Some(columns) => create_select_list(columns.iter().map(|c| c.as_str())),
+            };
+
+            let mut column_types = vec![];
+            let from = format!("({}) as extract_query", query);
+            let select_list =
+                prepare_columns(conn, select_list, &from, &mut column_types, options)?;
+
+            let order_by = match &options.order_by {
+                None => "".to_string(),
+                Some(OrderBy::Columns(columns)) => format!("order by {}", columns),
+                Some(OrderBy::PrimaryKey) => {
+                    return Err(Box::new(Errors::NoPrimaryKeyError("a query".to_string())))
+                }
+            };
+
+            let query = SELECT_FROM_QUERY_QUERY
+                .replace("XX_COLUMNS_XX", select_list.as_str())
+                .replace("XX_QUERY_XX", query.as_str())
+                .replace("XX_WHERE_XX", create_where(&conditions).as_str())
+                .replace("XX_ORDER_BY_XX", order_by.as_str())
+                .replace("XX_LIMIT_XX", limit.as_str());
+
+            (query, column_types)
+        }
+    };
 
-    let stmt = Statement::with_parent(&conn)?;
+    let mut stmt = Statement::with_parent(conn)?;
+
+    for (index, value) in param_values.iter().enumerate() {
+        stmt = stmt.bind_parameter((index + 1) as u16, value)?;
+    }
 
     match stmt.exec_direct(&query)? {
         NoData(_) => println!("no data returned"),
         Data(mut stmt) => {
             let mut output_file = File::create(&output_path)?;
-            output_file.write(&FILE_HEADER)?;
-            output_file.write(column_definitions.as_slice())?;
 
-            let cols = stmt.num_result_cols()?;
-            let mut nulls: Vec<bool> = vec![false; cols as usize];
-            let mut row_data: Vec<Vec<u8>> = vec![];
+            match options.format {
+                OutputFormat::Native => {
+                    output_file.write_all(&FILE_HEADER)?;
+                    output_file.write_all(&generate_column_definitions(
+                        &column_types,
+                        options.encoding,
+                    ))?;
+                }
+                OutputFormat::Csv if options.csv.header => {
+                    output_file.write_all(&create_csv_header(&column_types, &options.csv)?)?;
+                }
+                OutputFormat::Csv => {}
+            }
+
+            // sampled rows, tagged with their position so they can be written in order
+            let mut reservoir: Vec<(usize, Vec<u8>)> = vec![];
+            let mut row_number = 0;
+
+            // long values are read straight from the driver, a chunk at a time
+            let handle = unsafe { stmt.handle() };
 
             while let Some(mut cursor) = stmt.fetch()? {
-                for i in 1..(cols + 1) {
-                    let col_type = &column_types[(i - 1) as usize];
-
-                    let byte_val: Vec<u8> = match col_type.data_type {
-                        SqlDataType::Integer => extract_integer(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Interval => extract_interval(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Float => extract_float(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Char => extract_char(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Varchar => extract_varchar(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Boolean => extract_boolean(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Date => extract_date(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Timestamp | SqlDataType::TimestampTz => {
-                            extract_timestamp(&mut nulls, &mut cursor, i)?
-                        }
-                        SqlDataType::Time => extract_time(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::TimeTz => extract_timetz(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Varbinary => extract_varbinary(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Binary => extract_binary(&mut nulls, &mut cursor, i)?,
-                        SqlDataType::Numeric => {
-                            extract_numeric(&mut nulls, &mut cursor, i, col_type)?
-                        }
-                    };
-
-                    if byte_val.len() > 0 {
-                        &row_data.push(byte_val);
-                    }
+                row_number += 1;
+
+                let placement = match &mut sampler {
+                    None => Placement::Write,
+                    Some(sampler) => sampler.place_next(),
+                };
+
+                if let Placement::Skip = placement {
+                    continue;
                 }
 
-                let bitmap = create_nulls_bitmap(&nulls);
+                let row = match options.format {
+                    OutputFormat::Native => {
+                        read_native_row(&mut cursor, handle, &column_types, options)?
+                    }
+                    OutputFormat::Csv => read_csv_row(&mut cursor, handle, &column_types, options)?,
+                };
 
-                let row_size: u32 =
-                    bitmap.len() as u32 + (&row_data).iter().fold(0, |acc, x| acc + x.len()) as u32;
+                match placement {
+                    Placement::Store(slot) if slot < reservoir.len() => {
+                        reservoir[slot] = (row_number, row)
+                    }
+                    Placement::Store(_) => reservoir.push((row_number, row)),
+                    _ => output_file.write_all(&row)?,
+                }
+            }
 
-                let flattened_values = (&row_data)
-                    .into_iter()
-                    .flatten()
-                    .map(|v| *v)
-                    .collect::<Vec<u8>>();
+            reservoir.sort_by_key(|(row_number, _)| *row_number);
 
-                output_file.write(&row_size.to_le_bytes())?;
-                output_file.write(&bitmap.as_slice())?;
-                output_file.write_all(&flattened_values)?;
+            for (_, row) in reservoir {
+                output_file.write_all(&row)?;
+            }
 
-                row_data.clear();
-                nulls = vec![false; cols as usize];
+            if options.format == OutputFormat::Native && has_spatial_columns(&column_types) {
+                write_copy_columns(&column_types, output_path)?;
             }
         }
     };
@@ -118,10 +302,83 @@ pub fn extract(
     Ok(())
 }
 
+/// Read the current row as it's written to a native file.
+fn read_native_row(
+    cursor: &mut Cursor<Allocated, AutocommitOn>,
+    handle: ffi::SQLHSTMT,
+    column_types: &[ColumnType],
+    options: &ExtractOptions,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let cols = column_types.len() as i16;
+    let mut nulls: Vec<bool> = vec![false; cols as usize];
+    let mut row_data: Vec<Vec<u8>> = vec![];
+
+    for i in 1..(cols + 1) {
+        let col_type = &column_types[(i - 1) as usize];
+
+        let byte_val: Vec<u8> = match col_type.data_type {
+            SqlDataType::Integer => extract_integer(&mut nulls, cursor, i)?,
+            SqlDataType::Interval(_) | SqlDataType::IntervalYearMonth(_) => {
+                extract_interval(&mut nulls, cursor, i, col_type)?
+            }
+            SqlDataType::Float => extract_float(&mut nulls, cursor, i)?,
+            SqlDataType::Char => extract_char(&mut nulls, cursor, i, col_type, options.encoding)?,
+            SqlDataType::Varchar => {
+                extract_varchar(&mut nulls, cursor, i, col_type, options.encoding)?
+            }
+            SqlDataType::Boolean => extract_boolean(&mut nulls, cursor, i)?,
+            SqlDataType::Date => extract_date(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::Timestamp | SqlDataType::TimestampTz => {
+                extract_timestamp(&mut nulls, cursor, i, col_type)?
+            }
+            SqlDataType::Time => extract_time(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::TimeTz => extract_timetz(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::Varbinary => extract_varbinary(&mut nulls, cursor, i)?,
+            SqlDataType::Binary => extract_binary(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::Numeric => extract_numeric(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::Uuid => extract_uuid(&mut nulls, cursor, i, col_type)?,
+            SqlDataType::LongVarchar
+            | SqlDataType::LongVarbinary
+            | SqlDataType::Geometry
+            | SqlDataType::Geography => {
+                extract_long(&mut nulls, handle, i, col_type, options.encoding)?
+            }
+            SqlDataType::Unsupported(_) => {
+                unreachable!("unsupported columns are dealt with before fetching")
+            }
+        };
+
+        if !byte_val.is_empty() {
+            row_data.push(byte_val);
+        }
+    }
+
+    Ok(create_row(&nulls, &row_data))
+}
+
+/// A row as it's written to a native file: its length, the nulls bitmap, then the
+/// values of the non-null columns.
+fn create_row(nulls: &Vec<bool>, row_data: &Vec<Vec<u8>>) -> Vec<u8> {
+    let bitmap = create_nulls_bitmap(nulls);
+
+    let row_size: u32 =
+        bitmap.len() as u32 + row_data.iter().fold(0, |acc, x| acc + x.len()) as u32;
+
+    let mut row: Vec<u8> = row_size.to_le_bytes().to_vec();
+    row.extend_from_slice(&bitmap);
+
+    for value in row_data {
+        row.extend_from_slice(value);
+    }
+
+    row
+}
+
 fn extract_binary(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
     let value = cursor.get_data::<Vec<u8>>(i as u16)?;
     let return_value = match value {
@@ -129,7 +386,77 @@ fn extract_binary(
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => value,
+        Some(value) => fit_to_width(value, 0, col_type)?,
+    };
+
+    Ok(return_value)
+}
+
+/// Character data is read as bytes, since the driver's text conversion fails on
+/// anything that isn't valid UTF-8, and is then checked or transcoded here.
+fn decode_text(
+    value: Vec<u8>,
+    col_type: &ColumnType,
+    encoding: TextEncoding,
+) -> Result<Vec<u8>, Errors> {
+    encoding
+        .decode(value)
+        .ok_or_else(|| Errors::InvalidTextError(col_type.name.clone(), encoding.name().to_string()))
+}
+
+/// `char` and `binary` values take exactly the column's width in a native file, so
+/// pad short values (the driver may trim them), and refuse long ones rather than
+/// write a row that disagrees with the header.
+fn fit_to_width(mut value: Vec<u8>, pad: u8, col_type: &ColumnType) -> Result<Vec<u8>, Errors> {
+    let width = col_type.width as usize;
+
+    if value.len() > width {
+        return Err(Errors::ValueTooLongError(
+            col_type.name.clone(),
+            value.len(),
+            width,
+        ));
+    }
+
+    value.resize(width, pad);
+
+    Ok(value)
+}
+
+/// UUIDs are read as text, since the driver's binary form is a Windows `GUID` with its
+/// first fields little-endian, and written as their 16 bytes in order.
+fn extract_uuid(
+    nulls: &mut [bool],
+    cursor: &mut Cursor<Allocated, AutocommitOn>,
+    i: i16,
+    col_type: &ColumnType,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
+        None => {
+            nulls[(i - 1) as usize] = true;
+            vec![]
+        }
+        Some(value) => {
+            let digits: String = value.chars().filter(|c| *c != '-').collect();
+
+            let bytes: Option<Vec<u8>> = if digits.len() == 32 && digits.is_ascii() {
+                (0..16)
+                    .map(|index| u8::from_str_radix(&digits[(index * 2)..(index * 2 + 2)], 16).ok())
+                    .collect()
+            } else {
+                None
+            };
+
+            match bytes {
+                Some(bytes) => bytes,
+                None => {
+                    return Err(Box::new(Errors::InvalidValueError(
+                        col_type.name.clone(),
+                        value.to_string(),
+                    )))
+                }
+            }
+        }
     };
 
     Ok(return_value)
@@ -155,19 +482,41 @@ fn extract_char(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
+    encoding: TextEncoding,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let value = cursor.get_data::<&str>(i as u16)?;
+    let value = cursor.get_data::<Vec<u8>>(i as u16)?;
     let return_value = match value {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => value.as_bytes().to_vec(),
+        Some(value) if value.len() > col_type.width as usize => {
+            return Err(Box::new(Errors::ValueTooLongError(
+                col_type.name.clone(),
+                value.len(),
+                col_type.width as usize,
+            )))
+        }
+        Some(value) => {
+            let mut value = decode_text(value, col_type, encoding)?;
+            value.resize(char_width(col_type, encoding) as usize, b' ');
+            value
+        }
     };
 
     Ok(return_value)
 }
 
+/// The width of a `char` column in a native file. A value's width is measured in the
+/// column's own encoding, but transcoding can lengthen it, so a transcoded column is
+/// written as wide as its values could become.
+fn char_width(col_type: &ColumnType, encoding: TextEncoding) -> u32 {
+    col_type.width * encoding.max_growth()
+}
+
+/// Floats come from the driver as IEEE 754 doubles, the same as Vertica stores them, so
+/// their bits are written untouched: infinities, `-0.0`, subnormals and NaNs included.
 fn extract_float(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
@@ -178,7 +527,7 @@ fn extract_float(
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => value.to_le_bytes().to_vec(),
+        Some(value) => value.to_bits().to_le_bytes().to_vec(),
     };
 
     Ok(return_value)
@@ -212,42 +561,17 @@ fn extract_numeric(
             vec![]
         }
         Some(value) => {
-            let num = i128::from_str(value)?;
-            let exp = match col_type.scale {
-                None => 0,
-                Some(exp) => exp,
-            };
-            let mul = 10_i128.pow(exp as u32);
-            let unscaled = num * mul;
-            let unscaled_bytes = unscaled.to_be_bytes();
-
-            let mut unscaled_bytes: Vec<u8> = unscaled_bytes
-                .iter()
-                .rev()
-                .skip_while(|b| **b == 0)
-                .map(|b| *b)
-                .collect();
-
-            unscaled_bytes.reverse();
-
-            let byte_len = unscaled_bytes.len();
-            let mut padded_bytes = vec![0; (col_type.width as usize - byte_len) as usize];
-            padded_bytes.extend_from_slice(&unscaled_bytes);
-
-            if num < 0 {
-                negate(&mut padded_bytes, col_type.width as usize - byte_len);
-            }
-
-            let mut final_bytes: Vec<u8> = vec![];
-
-            for i in 0..(padded_bytes.len() / 8) {
-                let chunk = &padded_bytes[(i as usize) * 8..(i as usize + 1) * 8];
-                for byte in chunk.iter().rev() {
-                    final_bytes.push(*byte);
+            let width = numeric_width(col_type.precision);
+
+            match encode_numeric(value, width, col_type.scale.unwrap_or(0)) {
+                Some(bytes) => bytes,
+                None => {
+                    return Err(Box::new(Errors::InvalidValueError(
+                        col_type.name.clone(),
+                        value.to_string(),
+                    )))
                 }
             }
-
-            final_bytes
         }
     };
 
@@ -281,174 +605,150 @@ fn extract_timetz(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<Vec<u8>>(i as u16)? {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => {
-            let midnight = NaiveTime::from_hms_nano(0, 0, 0, 0);
-            let local_now = Local::now();
-            let local_local = local_now.naive_local();
-            let local_utc = local_now.naive_utc();
-
-            let hour = u16::from_le_bytes(value[0..2].try_into().unwrap());
-            let minute = u16::from_le_bytes(value[2..4].try_into().unwrap());
-            let second = u16::from_le_bytes(value[4..6].try_into().unwrap());
+        Some(value) => match parse_timetz(value) {
+            Some((micros, offset)) => {
+                let micros = round_to_precision(micros, col_type.precision);
 
-            let the_time = NaiveTime::from_hms(hour as u32, minute as u32, second as u32);
-
-            let diff = match (the_time - midnight).num_microseconds() {
-                None => 0,
-                Some(diff) => diff,
-            };
-
-            let tz_diff_seconds = (local_local - local_utc).num_seconds() + (24 * 60 * 60);
-
-            let total = (diff << 24) + tz_diff_seconds;
-
-            total.to_le_bytes().to_vec()
-        }
+                encode_timetz(micros, offset).to_le_bytes().to_vec()
+            }
+            None => {
+                return Err(Box::new(Errors::InvalidValueError(
+                    col_type.name.clone(),
+                    value.to_string(),
+                )))
+            }
+        },
     };
 
     Ok(return_value)
 }
 
+/// `SqlTime` has no fractional seconds, so times are read as text instead.
 fn extract_time(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<SqlTime>(i as u16)? {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => {
-            let midnight = NaiveTime::from_hms_nano(0, 0, 0, 0);
-
-            let the_time =
-                NaiveTime::from_hms(value.hour as u32, value.minute as u32, value.second as u32);
-
-            let diff = match (the_time - midnight).num_microseconds() {
-                None => 0,
-                Some(diff) => diff,
-            };
-
-            diff.to_le_bytes().to_vec()
-        }
+        Some(value) => match (parse_time(value), parse_timetz(value)) {
+            (Some(micros), _) => round_to_precision(micros, col_type.precision)
+                .to_le_bytes()
+                .to_vec(),
+            // a `timetz` in a query's result set is described as a `time`
+            (None, Some((micros, offset))) => {
+                let micros = round_to_precision(micros, col_type.precision);
+
+                encode_timetz(micros, offset).to_le_bytes().to_vec()
+            }
+            (None, None) => {
+                return Err(Box::new(Errors::InvalidValueError(
+                    col_type.name.clone(),
+                    value.to_string(),
+                )))
+            }
+        },
     };
 
     Ok(return_value)
 }
 
+/// Timestamps are read as text, since neither `SqlTimestamp` nor chrono can hold BC
+/// years or infinities.
 fn extract_timestamp(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<SqlTimestamp>(i as u16)? {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => {
-            let epoch = NaiveDate::from_ymd(2000, 1, 1).and_hms_milli(0, 0, 0, 0);
-            let the_date =
-                NaiveDate::from_ymd(value.year as i32, value.month as u32, value.day as u32)
-                    .and_hms_nano(
-                        value.hour as u32,
-                        value.minute as u32,
-                        value.second as u32,
-                        value.fraction as u32,
-                    );
-
-            let diff = match (the_date - epoch).num_microseconds() {
-                None => 0,
-                Some(diff) => diff,
-            };
-
-            diff.to_le_bytes().to_vec()
-        }
+        Some(value) => match parse_timestamp(value) {
+            Some(micros) if is_infinite(micros) => micros.to_le_bytes().to_vec(),
+            Some(micros) => round_to_precision(micros, col_type.precision)
+                .to_le_bytes()
+                .to_vec(),
+            None => {
+                return Err(Box::new(Errors::OutOfRangeError(
+                    col_type.name.clone(),
+                    value.to_string(),
+                )))
+            }
+        },
     };
 
     Ok(return_value)
 }
 
+/// Dates are read as text, for the same reasons as timestamps.
 fn extract_date(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<SqlDate>(i as u16)? {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
-        Some(value) => {
-            let epoch = NaiveDate::from_ymd(2000, 1, 1);
-            let the_date =
-                NaiveDate::from_ymd(value.year as i32, value.month as u32, value.day as u32);
-
-            let diff = (the_date - epoch).num_days();
-
-            diff.to_le_bytes().to_vec()
-        }
+        Some(value) => match parse_date(value) {
+            Some(days) => days.to_le_bytes().to_vec(),
+            None => {
+                return Err(Box::new(Errors::OutOfRangeError(
+                    col_type.name.clone(),
+                    value.to_string(),
+                )))
+            }
+        },
     };
 
     Ok(return_value)
 }
 
+/// Intervals are written as a count of their smallest unit: microseconds for day-time
+/// intervals, and months for year-month ones.
 fn extract_interval(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<Vec<u8>>(i as u16)? {
+    let return_value = match cursor.get_data::<&str>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
         Some(value) => {
-            value
-            // lazy_static! {
-            //     static ref chunker: Regex =
-            //         Regex::new(r"\d*\s*(\d+):(\d+):(\d+)\.(\d+)").unwrap();
-            // }
-            //
-            // if chunker.is_match(value) {
-            //     let captures = chunker.captures(value).unwrap();
-            //     let hours: u32 = captures
-            //         .get(1)
-            //         .map_or("", |m| m.as_str())
-            //         .parse()
-            //         .unwrap();
-            //     let minutes: u32 = captures
-            //         .get(2)
-            //         .map_or("", |m| m.as_str())
-            //         .parse()
-            //         .unwrap();
-            //     let seconds: u32 = captures
-            //         .get(3)
-            //         .map_or("", |m| m.as_str())
-            //         .parse()
-            //         .unwrap();
-            //     let millis: u32 = captures
-            //         .get(4)
-            //         .map_or("", |m| m.as_str())
-            //         .parse()
-            //         .unwrap();
-            //
-            //     let res = (hours * 3600000000)
-            //         + (minutes * 60000000)
-            //         + (seconds * 1000000)
-            //         + (millis * 1000);
-            //
-            //     res.to_le_bytes().to_vec()
-            // } else {
-            //     vec![]
-            // }
+            let count = match col_type.data_type {
+                SqlDataType::IntervalYearMonth(leading) => year_month_to_months(value, leading),
+                SqlDataType::Interval(leading) => day_time_to_microseconds(value, leading),
+                _ => None,
+            };
+
+            match count {
+                Some(count) => count.to_le_bytes().to_vec(),
+                None => {
+                    return Err(Box::new(Errors::InvalidValueError(
+                        col_type.name.clone(),
+                        value.to_string(),
+                    )))
+                }
+            }
         }
     };
 
@@ -459,18 +759,20 @@ fn extract_varchar(
     nulls: &mut Vec<bool>,
     cursor: &mut Cursor<Allocated, AutocommitOn>,
     i: i16,
+    col_type: &ColumnType,
+    encoding: TextEncoding,
 ) -> Result<Vec<u8>, Box<dyn Error>> {
-    let return_value = match cursor.get_data::<&str>(i as u16)? {
+    let return_value = match cursor.get_data::<Vec<u8>>(i as u16)? {
         None => {
             nulls[(i - 1) as usize] = true;
             vec![]
         }
         Some(value) => {
-            let bytes = value.as_bytes();
+            let bytes = decode_text(value, col_type, encoding)?;
             let byte_len: u32 = bytes.len() as u32;
 
             let mut rec: Vec<u8> = byte_len.to_le_bytes().to_vec();
-            rec.extend_from_slice(bytes);
+            rec.extend(bytes);
 
             rec
         }
@@ -479,10 +781,36 @@ fn extract_varchar(
     Ok(return_value)
 }
 
-fn negate(bytes: &mut [u8], head: usize) {
-    for i in 0..head {
-        bytes[i] ^= 0xFF;
-    }
+fn extract_long(
+    nulls: &mut [bool],
+    handle: ffi::SQLHSTMT,
+    i: i16,
+    col_type: &ColumnType,
+    encoding: TextEncoding,
+) -> Result<Vec<u8>, Box<dyn Error>> {
+    let value =
+        read_long_data(handle, i as u16).map_err(|_| Errors::ReadError(col_type.name.clone()))?;
+
+    let return_value = match value {
+        None => {
+            nulls[(i - 1) as usize] = true;
+            vec![]
+        }
+        Some(value) => {
+            let value = match col_type.data_type {
+                SqlDataType::LongVarchar => decode_text(value, col_type, encoding)?,
+                _ => value,
+            };
+            let byte_len: u32 = value.len() as u32;
+
+            let mut rec: Vec<u8> = byte_len.to_le_bytes().to_vec();
+            rec.extend(value);
+
+            rec
+        }
+    };
+
+    Ok(return_value)
 }
 
 fn create_nulls_bitmap(nulls: &Vec<bool>) -> Vec<u8> {
@@ -503,7 +831,7 @@ fn create_nulls_bitmap(nulls: &Vec<bool>) -> Vec<u8> {
     bitmap
 }
 
-fn generate_column_definitions(column_types: &Vec<ColumnType>) -> Vec<u8> {
+fn generate_column_definitions(column_types: &Vec<ColumnType>, encoding: TextEncoding) -> Vec<u8> {
     // file version; only supported version is `1`
     let mut bytes: Vec<u8> = 1_u16.to_le_bytes().to_vec();
 
@@ -515,24 +843,27 @@ fn generate_column_definitions(column_types: &Vec<ColumnType>) -> Vec<u8> {
 
     for column_type in column_types {
         let width: u32 = match column_type.data_type {
-            SqlDataType::Integer | SqlDataType::Char | SqlDataType::Binary => {
-                column_type.width as u32
-            }
-            SqlDataType::Varchar | SqlDataType::Varbinary => -1_i32 as u32,
+            SqlDataType::Integer | SqlDataType::Binary => column_type.width,
+            SqlDataType::Char => char_width(column_type, encoding),
+            SqlDataType::Varchar
+            | SqlDataType::Varbinary
+            | SqlDataType::LongVarchar
+            | SqlDataType::LongVarbinary
+            | SqlDataType::Geometry
+            | SqlDataType::Geography => -1_i32 as u32,
             SqlDataType::Boolean => 1,
+            SqlDataType::Uuid => 16,
             SqlDataType::Float
             | SqlDataType::Date
             | SqlDataType::Timestamp
             | SqlDataType::TimestampTz
             | SqlDataType::Time
             | SqlDataType::TimeTz
-            | SqlDataType::Interval => 8,
-            SqlDataType::Numeric => {
-                if let Some(precision) = column_type.precision {
-                    (((precision / 19) + 1) * 8) as u32
-                } else {
-                    0
-                }
+            | SqlDataType::Interval(_)
+            | SqlDataType::IntervalYearMonth(_) => 8,
+            SqlDataType::Numeric => numeric_width(column_type.precision) as u32,
+            SqlDataType::Unsupported(_) => {
+                unreachable!("unsupported columns are dealt with before writing")
             }
         };
 
@@ -547,54 +878,403 @@ fn generate_column_definitions(column_types: &Vec<ColumnType>) -> Vec<u8> {
     header
 }
 
-fn get_column_types<'env>(
-    conn: &Connection<'env, AutocommitOn>,
-    table: &String,
+/// Pick out the named columns, in the order they were named, failing if any of them
+/// aren't in the table or are named more than once.
+fn select_columns(
+    column_types: Vec<ColumnType>,
+    columns: &[String],
 ) -> std::result::Result<Vec<ColumnType>, Box<dyn Error>> {
-    let query = GET_COLUMN_DEFINITIONS_QUERY.replace("XX_TABLE_NAME_XX", table.as_str());
-    let stmt = Statement::with_parent(&conn)?;
+    let mut available: Vec<Option<ColumnType>> = column_types.into_iter().map(Some).collect();
+    let mut selected: Vec<ColumnType> = vec![];
+    let mut unknown: Vec<String> = vec![];
+
+    for column in columns {
+        let position = available.iter().position(|c| match c {
+            Some(c) => c.name.eq_ignore_ascii_case(column),
+            None => false,
+        });
+
+        match position {
+            Some(position) => selected.push(available[position].take().unwrap()),
+            None if selected.iter().any(|c| c.name.eq_ignore_ascii_case(column)) => {
+                return Err(Box::new(Errors::DuplicateColumnError(column.clone())));
+            }
+            None => unknown.push(column.clone()),
+        }
+    }
 
-    let mut column_types: Vec<ColumnType> = vec![];
+    if !unknown.is_empty() {
+        return Err(Box::new(Errors::UnknownColumnsError(unknown)));
+    }
 
-    match stmt.exec_direct(&query)? {
-        Data(mut stmt) => {
-            let cols = stmt.num_result_cols()?;
+    Ok(selected)
+}
 
-            while let Some(mut cursor) = stmt.fetch()? {
-                let mut values: Vec<String> = vec![];
+fn create_where(conditions: &[String]) -> String {
+    if conditions.is_empty() {
+        "".to_string()
+    } else {
+        let conditions: Vec<String> = conditions.iter().map(|c| format!("({})", c)).collect();
+        format!("where {}", conditions.join(" and "))
+    }
+}
 
-                for i in 1..(cols + 1) {
-                    match cursor.get_data::<&str>(i as u16)? {
-                        Some(val) => values.push(val.to_string()),
-                        None => values.push("".to_string()),
-                    }
+fn create_select_list<'a>(columns: impl Iterator<Item = &'a str>) -> String {
+    columns
+        .map(quote_identifier)
+        .collect::<Vec<String>>()
+        .join(", ")
+}
+
+/// Get the columns ready to extract: describe them, if the catalog couldn't, and deal
+/// with any of unsupported types according to `options.unsupported_types`, all before
+/// a row is fetched. Returns the select list to use, which is rewritten if any
+/// columns need converting by Vertica.
+fn prepare_columns<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    select_list: String,
+    from: &str,
+    column_types: &mut Vec<ColumnType>,
+    options: &ExtractOptions,
+) -> std::result::Result<String, Box<dyn Error>> {
+    // a query's columns (or a relation's the catalog doesn't describe) come from its
+    // result set
+    let described = column_types.is_empty();
+
+    if described {
+        let query = format!("select {} from {} limit 0", select_list, from);
+        *column_types = describe_columns(conn, &query)?;
+    }
+
+    // a cast column takes the type it's cast to
+    let mut casts: Vec<Option<&str>> = vec![None; column_types.len()];
+    let mut unknown: Vec<String> = vec![];
+
+    for (column, type_name) in &options.casts {
+        let position = column_types
+            .iter()
+            .position(|c| c.name.eq_ignore_ascii_case(column));
+
+        match position {
+            Some(position) => {
+                let column_type = cast_column_type(&column_types[position].name, type_name);
+
+                if let SqlDataType::Unsupported(_) = column_type.data_type {
+                    return Err(Box::new(Errors::UnsupportedTypeError(vec![format!(
+                        "{} ({})",
+                        column, type_name
+                    )])));
                 }
 
-                column_types.push(ColumnType::new(&values));
+                column_types[position] = column_type;
+                casts[position] = Some(type_name.as_str());
             }
+            None => unknown.push(column.clone()),
         }
-        NoData(_) => return Err(Box::new(errors::Errors::TableNotFoundError)),
+    }
+
+    if !unknown.is_empty() {
+        return Err(Box::new(Errors::UnknownColumnsError(unknown)));
+    }
+
+    // a result set describes `timestamptz` columns as `timestamp`, and converting one
+    // as though it were plain would shift its values, so only the catalog is trusted
+    if described && options.timezone.is_some() {
+        let ambiguous: Vec<String> = column_types
+            .iter()
+            .zip(&casts)
+            .filter(|(column_type, cast)| {
+                cast.is_none() && column_type.data_type == SqlDataType::Timestamp
+            })
+            .map(|(column_type, _)| column_type.name.clone())
+            .collect();
+
+        if !ambiguous.is_empty() {
+            return Err(Box::new(Errors::AmbiguousTimestampError(ambiguous)));
+        }
+    }
+
+    let unsupported: Vec<String> = column_types
+        .iter()
+        .filter_map(|column_type| match &column_type.data_type {
+            SqlDataType::Unsupported(name) => Some(format!("{} ({})", column_type.name, name)),
+            _ => None,
+        })
+        .collect();
+
+    let date_format = match options.format {
+        OutputFormat::Csv => options.csv.date_format.as_ref(),
+        OutputFormat::Native => None,
     };
 
+    let timestamp_format = match options.format {
+        OutputFormat::Csv => options.csv.timestamp_format.as_ref(),
+        OutputFormat::Native => None,
+    };
+
+    if unsupported.is_empty()
+        && options.casts.is_empty()
+        && options.timezone.is_none()
+        && date_format.is_none()
+        && timestamp_format.is_none()
+        && !has_spatial_columns(column_types)
+    {
+        return Ok(select_list);
+    }
+
+    if !unsupported.is_empty() {
+        match options.unsupported_types {
+            UnsupportedTypes::Fail => {
+                return Err(Box::new(Errors::UnsupportedTypeError(unsupported)))
+            }
+            UnsupportedTypes::Skip if unsupported.len() == column_types.len() => {
+                return Err(Box::new(Errors::UnsupportedTypeError(unsupported)))
+            }
+            UnsupportedTypes::Skip => {
+                eprintln!("skipping unsupported columns: {}", unsupported.join(", "));
+            }
+            UnsupportedTypes::Cast => {
+                eprintln!(
+                    "casting unsupported columns to varchar: {}",
+                    unsupported.join(", ")
+                );
+            }
+        }
+    }
+
+    let mut select_list: Vec<String> = vec![];
+
+    for (column_type, cast) in column_types.iter_mut().zip(casts) {
+        let name = quote_identifier(&column_type.name);
+
+        let value = match (cast, &column_type.data_type, &options.timezone) {
+            (Some(type_name), _, _) => format!("{}::{}", name, type_name),
+            (None, SqlDataType::Unsupported(_), _) => {
+                if options.unsupported_types == UnsupportedTypes::Cast {
+                    column_type.data_type = SqlDataType::Varchar;
+                    format!("{}::varchar(65000)", name)
+                } else {
+                    continue;
+                }
+            }
+            (None, SqlDataType::Geometry, _) | (None, SqlDataType::Geography, _) => {
+                format!("ST_AsBinary({})", name)
+            }
+            // plain timestamps are read as being in `timezone`, and converted to UTC
+            (None, SqlDataType::Timestamp, Some(timezone)) => format!(
+                "({} at time zone {})::timestamp",
+                name,
+                quote_literal(timezone)
+            ),
+            _ => name.clone(),
+        };
+
+        // dates and timestamps written to CSV are formatted by Vertica
+        let format = match column_type.data_type {
+            SqlDataType::Date => date_format,
+            SqlDataType::Timestamp | SqlDataType::TimestampTz => timestamp_format,
+            _ => None,
+        };
+
+        let value = match format {
+            Some(format) => format!("to_char({}, {})", value, quote_literal(format)),
+            None => value,
+        };
+
+        if value == name {
+            select_list.push(name);
+        } else {
+            select_list.push(format!("{} as {}", value, name));
+        }
+    }
+
+    column_types
+        .retain(|column_type| !matches!(column_type.data_type, SqlDataType::Unsupported(_)));
+
+    Ok(select_list.join(", "))
+}
+
+/// The types of a query's columns, from its result set.
+fn describe_columns<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    query: &str,
+) -> std::result::Result<Vec<ColumnType>, Box<dyn Error>> {
+    let stmt = Statement::with_parent(conn)?;
+    let mut column_types: Vec<ColumnType> = vec![];
+
+    if let Data(stmt) = stmt.exec_direct(query)? {
+        let handle = unsafe { stmt.handle() };
+
+        for i in 1..(stmt.num_result_cols()? + 1) {
+            let description = describe_column(handle, i as u16)
+                .map_err(|_| Errors::ReadError(format!("#{}", i)))?;
+            column_types.push(ColumnType::from_description(&description));
+        }
+    }
+
+    Ok(column_types)
+}
+
+/// The table's column types, from the catalog of tables, views or system tables,
+/// in column order. This is empty if the catalog doesn't describe the table's columns.
+fn get_column_types<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &TableName,
+) -> std::result::Result<Vec<ColumnType>, Box<dyn Error>> {
+    let schema = table.schema.clone().unwrap_or_default();
+    let query = GET_COLUMN_DEFINITIONS_QUERY
+        .replace("XX_SCHEMA_NAME_XX", quote_literal(&schema).as_str())
+        .replace("XX_TABLE_NAME_XX", quote_literal(&table.name).as_str());
+
+    let column_types: Vec<ColumnType> = fetch_rows(conn, &query)?
+        .iter()
+        .map(ColumnType::new)
+        .collect();
+
     Ok(column_types)
 }
 
-fn create_dsn(
-    server: String,
-    port: u16,
-    database: String,
-    username: String,
-    password: Option<String>,
-) -> String {
-    format!(
-        "Driver=Vertica;ServerName={};Port={};Database={};UID={}{}",
-        server,
-        port,
-        database,
-        username,
-        match password {
-            None => "".to_string(),
-            Some(password) => format!(";PWD={}", password),
-        }
-    )
+/// The table's primary key columns, in key order.
+fn get_primary_key<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &TableName,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let schema = table.schema.clone().unwrap_or_default();
+    let query = GET_PRIMARY_KEY_QUERY
+        .replace("XX_SCHEMA_NAME_XX", quote_literal(&schema).as_str())
+        .replace("XX_TABLE_NAME_XX", quote_literal(&table.name).as_str());
+
+    let key: Vec<String> = fetch_rows(conn, &query)?
+        .into_iter()
+        .map(|mut values| values.remove(0))
+        .collect();
+
+    if key.is_empty() {
+        return Err(Box::new(Errors::NoPrimaryKeyError(table.to_string())));
+    }
+
+    Ok(key)
+}
+
+/// Work out which table an (optionally schema-qualified) name refers to.
+///
+/// An unqualified name is looked up in the session's search path, the same way
+/// Vertica would. If none of the matches are on the search path, the table isn't
+/// found; when there are several, the error names them all.
+fn resolve_table<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &str,
+) -> std::result::Result<TableName, Box<dyn Error>> {
+    let table_name = TableName::parse(table);
+
+    let query =
+        FIND_TABLES_QUERY.replace("XX_TABLE_NAME_XX", quote_literal(&table_name.name).as_str());
+
+    let mut candidates: Vec<TableName> = fetch_rows(conn, &query)?
+        .iter()
+        .map(|values| TableName::qualified(&values[0], &values[1]))
+        .collect();
+
+    let in_schema = |candidate: &TableName, schema: &str| match &candidate.schema {
+        None => false,
+        Some(candidate_schema) => candidate_schema.eq_ignore_ascii_case(schema),
+    };
+
+    if let Some(schema) = &table_name.schema {
+        return candidates
+            .into_iter()
+            .find(|candidate| in_schema(candidate, schema))
+            .ok_or_else(|| Errors::TableNotFoundError(table.to_string()).into());
+    }
+
+    if candidates.is_empty() {
+        return Err(Box::new(Errors::TableNotFoundError(table.to_string())));
+    }
+
+    // like Vertica, only look in the schemas on the search path, in order
+    for schema in get_search_path(conn)? {
+        if let Some(index) = candidates.iter().position(|c| in_schema(c, &schema)) {
+            return Ok(candidates.remove(index));
+        }
+    }
+
+    if candidates.len() == 1 {
+        Err(Box::new(Errors::TableNotFoundError(table.to_string())))
+    } else {
+        Err(Box::new(Errors::AmbiguousTableError(
+            table.to_string(),
+            candidates.iter().map(|c| c.to_string()).collect(),
+        )))
+    }
+}
+
+/// The session's search path, in order, with `$user` replaced by the current user.
+fn get_search_path<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+) -> std::result::Result<Vec<String>, Box<dyn Error>> {
+    let current_user = match fetch_rows(conn, GET_CURRENT_USER_QUERY)?.first() {
+        Some(values) => values[0].clone(),
+        None => "".to_string(),
+    };
+
+    let setting = match fetch_rows(conn, GET_SEARCH_PATH_QUERY)?.first() {
+        Some(values) => values[1].clone(),
+        None => "".to_string(),
+    };
+
+    let search_path = setting
+        .split(',')
+        .map(|schema| schema.trim().trim_matches('"'))
+        .filter(|schema| !schema.is_empty())
+        .map(|schema| {
+            if schema == "$user" {
+                current_user.clone()
+            } else {
+                schema.to_string()
+            }
+        })
+        .collect();
+
+    Ok(search_path)
+}
+
+/// Run a statement, ignoring any results.
+fn execute<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    query: &str,
+) -> std::result::Result<(), Box<dyn Error>> {
+    let stmt = Statement::with_parent(conn)?;
+    stmt.exec_direct(query)?;
+
+    Ok(())
+}
+
+/// Run a query and return every row, with each value as a string; `NULL`s become
+/// empty strings.
+fn fetch_rows<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    query: &str,
+) -> std::result::Result<Vec<Vec<String>>, Box<dyn Error>> {
+    let stmt = Statement::with_parent(conn)?;
+
+    let mut rows: Vec<Vec<String>> = vec![];
+
+    if let Data(mut stmt) = stmt.exec_direct(query)? {
+        let cols = stmt.num_result_cols()?;
+
+        while let Some(mut cursor) = stmt.fetch()? {
+            let mut values: Vec<String> = vec![];
+
+            for i in 1..(cols + 1) {
+                match cursor.get_data::<&str>(i as u16)? {
+                    Some(val) => values.push(val.to_string()),
+                    None => values.push("".to_string()),
+                }
+            }
+
+            rows.push(values);
+        }
+    }
+
+    Ok(rows)
 }
@@ -0,0 +1,62 @@
+use odbc::ffi;
+
+/// How much of a long value to read at a time.
+const CHUNK_SIZE: usize = 64 * 1024;
+
+/// Read a `long varchar` or `long varbinary` value as raw bytes, a chunk at a time,
+/// straight into a buffer of the value's size. The odbc crate's `get_data` instead
+/// doubles its buffer until the value fits, then copies it out.
+///
+/// `stmt` must be the handle of a statement positioned on a row, by a cursor that's
+/// still open. Returns `None` for `NULL`, and `Err` if the driver reports an error.
+pub(crate) fn read_long_data(stmt: ffi::SQLHSTMT, column: u16) -> Result<Option<Vec<u8>>, ()> {
+    let mut value: Vec<u8> = vec![];
+    let mut chunk = vec![0_u8; CHUNK_SIZE];
+
+    loop {
+        let mut indicator: ffi::SQLLEN = 0;
+
+        let result = unsafe {
+            ffi::SQLGetData(
+                stmt,
+                column,
+                ffi::SQL_C_BINARY,
+                chunk.as_mut_ptr() as ffi::SQLPOINTER,
+                chunk.len() as ffi::SQLLEN,
+                &mut indicator as *mut ffi::SQLLEN,
+            )
+        };
+
+        let complete = match result {
+            ffi::SQL_NO_DATA => break,
+            ffi::SQL_SUCCESS => true,
+            ffi::SQL_SUCCESS_WITH_INFO => {
+                indicator != ffi::SQL_NO_TOTAL && indicator as usize <= chunk.len()
+            }
+            _ => return Err(()),
+        };
+
+        if indicator == ffi::SQL_NULL_DATA {
+            return Ok(None);
+        }
+
+        // the first call says how big the whole value is, if the driver knows
+        if value.is_empty() && indicator > 0 {
+            value.reserve(indicator as usize);
+        }
+
+        let length = if complete {
+            indicator as usize
+        } else {
+            chunk.len()
+        };
+
+        value.extend_from_slice(&chunk[..length]);
+
+        if complete {
+            break;
+        }
+    }
+
+    Ok(Some(value))
+}
@@ -1,10 +1,17 @@
 use std::env;
+use std::fmt::Display;
+use std::fs;
 use std::path::Path;
+use std::process;
 
 use colored::*;
-use getopts::Options;
+use getopts::{Matches, Options};
 
-use verticaextractor::extract;
+use verticaextractor::{
+    extract, extract_partitions, extract_schema, list_partitions, parse_cast, parse_param, run_job,
+    BinaryEncoding, ConnectionOptions, CsvOptions, ExtractOptions, ExtractOutcome, Job, OrderBy,
+    OutputFormat, Quoting, Sample, Source, TextEncoding, UnsupportedTypes,
+};
 
 const VERSION: &'static str = env!("CARGO_PKG_VERSION");
 
@@ -20,7 +27,38 @@ fn main() {
     let table_help = format!("table to extract {}", "*".bright_red());
     opts.optopt("t", "table", table_help.as_str(), "NAME");
 
-    let output_help = format!("output file name {}", "*".bright_red());
+    let query_help = format!("query to extract, instead of <table> {}", "*".bright_red());
+    opts.optopt("q", "query", query_help.as_str(), "SQL");
+
+    let query_file_help = format!(
+        "file containing a query to extract, instead of <table> {}",
+        "*".bright_red()
+    );
+    opts.optopt("Q", "query-file", query_file_help.as_str(), "FILE");
+
+    let schema_help = format!(
+        "schema whose tables should all be extracted, into the <output> directory {}",
+        "*".bright_red()
+    );
+    opts.optopt("S", "schema", schema_help.as_str(), "NAME");
+
+    opts.optmulti(
+        "",
+        "include",
+        "only extract <schema> tables matching this glob; may be repeated",
+        "GLOB",
+    );
+    opts.optmulti(
+        "",
+        "exclude",
+        "don't extract <schema> tables matching this glob; may be repeated",
+        "GLOB",
+    );
+
+    let output_help = format!(
+        "output file name, or directory for <schema> {}",
+        "*".bright_red()
+    );
     opts.optopt("o", "output", output_help.as_str(), "NAME");
 
     let server_help = format!(
@@ -38,13 +76,152 @@ fn main() {
 
     opts.optflag("f", "force", "overwrite destination file");
 
+    opts.optopt(
+        "c",
+        "columns",
+        "comma-separated columns to extract, in order [default: all]",
+        "NAMES",
+    );
+
+    opts.optopt(
+        "w",
+        "where",
+        "only extract rows matching this predicate",
+        "PREDICATE",
+    );
+
+    opts.optmulti(
+        "",
+        "param",
+        "value for a :NAME placeholder in <where>; may be repeated",
+        "NAME=VALUE",
+    );
+    opts.optmulti(
+        "",
+        "cast",
+        "have Vertica cast COLUMN to TYPE, to match the table it'll be loaded into; may be \
+         repeated",
+        "COLUMN=TYPE",
+    );
+
+    opts.optopt(
+        "O",
+        "order-by",
+        "order rows by these columns, or by the table's \"primary key\"",
+        "COLUMNS",
+    );
+
     opts.optopt(
         "l",
         "limit",
-        "maximum number of rows to extract from <table>",
+        "maximum number of rows to extract from <table> or <query>",
         "NUMBER",
     );
 
+    opts.optopt(
+        "",
+        "sample-percent",
+        "extract a random sample of about this percent of the rows",
+        "PERCENT",
+    );
+    opts.optopt(
+        "",
+        "sample-rows",
+        "extract a random sample of this many rows",
+        "NUMBER",
+    );
+    opts.optopt(
+        "",
+        "seed",
+        "seed for the random sample, to pick the same rows each time",
+        "NUMBER",
+    );
+    opts.optopt(
+        "",
+        "timezone",
+        "time zone that timestamp values are in, to convert them to UTC",
+        "ZONE",
+    );
+    opts.optopt(
+        "",
+        "unsupported-types",
+        "what to do with columns of unsupported types: fail (the default), skip them, or cast them to varchar",
+        "fail|skip|cast",
+    );
+    opts.optopt(
+        "",
+        "encoding",
+        "encoding of character data, to transcode to UTF-8; or raw, to write it byte for byte",
+        "NAME",
+    );
+
+    opts.optopt(
+        "",
+        "format",
+        "kind of file to write: native (the default) or csv",
+        "native|csv",
+    );
+    opts.optopt(
+        "",
+        "delimiter",
+        "character between csv values, or \\t for a tab [default: ,]",
+        "CHAR",
+    );
+    opts.optopt(
+        "",
+        "quote",
+        "character to enclose csv values in [default: \"]",
+        "CHAR",
+    );
+    opts.optopt(
+        "",
+        "quoting",
+        "which csv values to quote: necessary (the default), always or never",
+        "necessary|always|never",
+    );
+    opts.optopt(
+        "",
+        "null",
+        "what null is written as in csv [default: empty]",
+        "STRING",
+    );
+    opts.optflag("", "header", "start csv files with a row of column names");
+    opts.optopt(
+        "",
+        "date-format",
+        "Vertica TO_CHAR pattern for dates in csv, e.g. MM/DD/YYYY",
+        "PATTERN",
+    );
+    opts.optopt(
+        "",
+        "timestamp-format",
+        "Vertica TO_CHAR pattern for timestamps in csv",
+        "PATTERN",
+    );
+    opts.optopt(
+        "",
+        "binary-encoding",
+        "how binary values are written in csv: octal (the default), hex or base64",
+        "octal|hex|base64",
+    );
+
+    opts.optopt(
+        "",
+        "partitions",
+        "comma-separated partition keys of <table> to extract",
+        "KEYS",
+    );
+    opts.optflag(
+        "",
+        "split-partitions",
+        "extract each partition of <table> into its own file in the <output> directory",
+    );
+    opts.optflag(
+        "",
+        "list-partitions",
+        "list the partition keys of <table>, instead of extracting it",
+    );
+
     opts.optflag("h", "help", "display this help message");
     opts.optflag("v", "version", "display the program version");
 
@@ -63,11 +240,27 @@ fn main() {
         return;
     }
 
+    // `run <jobfile>` takes everything from the job file, rather than the options
+    if matches.free.first().map(|s| s.as_str()) == Some("run") {
+        match matches.free.get(1) {
+            Some(job_file) => run(job_file, matches.opt_present("f")),
+            None => {
+                eprintln!("{}", "\nerror: job file name is required\n".bright_red());
+                print_usage(&program, opts);
+            }
+        }
+
+        return;
+    }
+
     // If no options at all, or `-h` are passed, print out the help,
     // without an error, and exit
     if matches.opt_present("h")
         || !matches.opts_present(&[
             "table".to_string(),
+            "query".to_string(),
+            "query-file".to_string(),
+            "schema".to_string(),
             "output".to_string(),
             "database".to_string(),
         ])
@@ -121,7 +314,10 @@ fn main() {
         }
     };
 
+    let listing_partitions = matches.opt_present("list-partitions");
+
     let output = match matches.opt_str("o") {
+        None if listing_partitions => "".to_string(),
         None => {
             eprintln!("{}", "\nerror: output file name is required\n".bright_red());
             print_usage(&program, opts);
@@ -130,13 +326,41 @@ fn main() {
         Some(o) => o,
     };
 
-    let table = match matches.opt_str("t") {
-        None => {
-            eprintln!("{}", "\nerror: table name is required\n".bright_red());
+    // `None` means every table in a schema is being extracted
+    let source = match (
+        matches.opt_str("t"),
+        matches.opt_str("q"),
+        matches.opt_str("Q"),
+        matches.opt_str("S"),
+    ) {
+        (Some(table), None, None, None) => Some(Source::Table(table)),
+        (None, Some(query), None, None) => Some(Source::query(&query)),
+        (None, None, Some(query_file), None) => match fs::read_to_string(&query_file) {
+            Ok(query) => Some(Source::query(&query)),
+            Err(e) => {
+                let msg = format!("\nerror: reading query file [{}]: {}\n", query_file, e);
+                eprintln!("{}", msg.bright_red());
+                return;
+            }
+        },
+        (None, None, None, Some(_)) => None,
+        (None, None, None, None) => {
+            eprintln!(
+                "{}",
+                "\nerror: table name, query, query file or schema is required\n".bright_red()
+            );
+            print_usage(&program, opts);
+            return;
+        }
+        _ => {
+            eprintln!(
+                "{}",
+                "\nerror: only one of table name, query, query file or schema may be given\n"
+                    .bright_red()
+            );
             print_usage(&program, opts);
             return;
         }
-        Some(table) => table,
     };
 
     let limit = match matches.opt_get::<usize>("l") {
@@ -151,9 +375,166 @@ fn main() {
         }
     };
 
+    let columns = matches
+        .opt_str("c")
+        .map(|columns| ExtractOptions::parse_list(&columns));
+
+    let mut params: Vec<(String, String)> = vec![];
+
+    for param in matches.opt_strs("param") {
+        match parse_param(&param) {
+            Ok(param) => params.push(param),
+            Err(e) => {
+                let msg = format!("\n{}\n", e);
+                eprintln!("{}", msg.bright_red());
+                print_usage(&program, opts);
+                return;
+            }
+        }
+    }
+
+    let mut casts: Vec<(String, String)> = vec![];
+
+    for cast in matches.opt_strs("cast") {
+        match parse_cast(&cast) {
+            Ok(cast) => casts.push(cast),
+            Err(e) => {
+                let msg = format!("\n{}\n", e);
+                eprintln!("{}", msg.bright_red());
+                print_usage(&program, opts);
+                return;
+            }
+        }
+    }
+
+    let sample = match (
+        matches.opt_get::<f64>("sample-percent"),
+        matches.opt_get::<usize>("sample-rows"),
+    ) {
+        (Ok(None), Ok(None)) => None,
+        (Ok(Some(percent)), Ok(None)) => Some(Sample::Percent(percent)),
+        (Ok(None), Ok(Some(rows))) => Some(Sample::Rows(rows)),
+        (Ok(Some(_)), Ok(Some(_))) => {
+            eprintln!(
+                "{}",
+                "\nerror: only one of sample percent or sample rows may be given\n".bright_red()
+            );
+            print_usage(&program, opts);
+            return;
+        }
+        _ => {
+            eprintln!(
+                "{}",
+                "\nerror: sample percent and sample rows must be given as numbers\n".bright_red()
+            );
+            print_usage(&program, opts);
+            return;
+        }
+    };
+
+    let seed = match matches.opt_get::<u64>("seed") {
+        Ok(seed) => seed,
+        Err(_) => {
+            eprintln!(
+                "{}",
+                "\nerror: seed must be given as an integer\n".bright_red()
+            );
+            print_usage(&program, opts);
+            return;
+        }
+    };
+
+    let unsupported_types = match matches.opt_str("unsupported-types") {
+        None => UnsupportedTypes::default(),
+        Some(policy) => match UnsupportedTypes::parse(&policy) {
+            Some(policy) => policy,
+            None => {
+                eprintln!(
+                    "{}",
+                    "\nerror: unsupported types must be one of fail, skip or cast\n".bright_red()
+                );
+                print_usage(&program, opts);
+                return;
+            }
+        },
+    };
+
+    let encoding = match matches.opt_str("encoding") {
+        None => TextEncoding::default(),
+        Some(name) => match TextEncoding::parse(&name) {
+            Some(encoding) => encoding,
+            None => {
+                let msg = format!("\nerror: unknown encoding [{}]\n", name);
+                eprintln!("{}", msg.bright_red());
+                print_usage(&program, opts);
+                return;
+            }
+        },
+    };
+
+    let format = match matches.opt_str("format") {
+        None => OutputFormat::default(),
+        Some(format) => match OutputFormat::parse(&format) {
+            Some(format) => format,
+            None => {
+                eprintln!(
+                    "{}",
+                    "\nerror: format must be one of native or csv\n".bright_red()
+                );
+                print_usage(&program, opts);
+                return;
+            }
+        },
+    };
+
+    let csv = match csv_options(&matches) {
+        Ok(csv) => csv,
+        Err(msg) => {
+            let msg = format!("\nerror: {}\n", msg);
+            eprintln!("{}", msg.bright_red());
+            print_usage(&program, opts);
+            return;
+        }
+    };
+
+    let options = ExtractOptions {
+        columns,
+        filter: matches.opt_str("w"),
+        params,
+        partitions: matches
+            .opt_str("partitions")
+            .map(|keys| ExtractOptions::parse_list(&keys)),
+        order_by: matches.opt_str("O").map(|o| OrderBy::parse(&o)),
+        limit,
+        sample,
+        seed,
+        timezone: matches.opt_str("timezone"),
+        casts,
+        unsupported_types,
+        encoding,
+        format,
+        csv,
+    };
+
     let output_path = Path::new(&output);
+    let force = matches.opt_present("f");
+    let splitting_partitions = matches.opt_present("split-partitions");
+
+    if (listing_partitions || splitting_partitions) && !matches.opt_present("t") {
+        eprintln!(
+            "{}",
+            "\nerror: partitions can only be listed or split for a table\n".bright_red()
+        );
+        print_usage(&program, opts);
+        return;
+    }
 
-    if output_path.exists() && !matches.opt_present("f") {
+    if source.is_some()
+        && !listing_partitions
+        && !splitting_partitions
+        && output_path.exists()
+        && !force
+    {
         let msg = format!("\nerror: file [{}] exists; use `-f` to force\n", output);
         eprintln!("{}", msg.bright_red());
         return;
@@ -164,22 +545,115 @@ fn main() {
         Some(password) => Some(password),
     };
 
-    match extract(
+    let connection = ConnectionOptions {
         server,
         port,
         database,
         username,
         password,
-        table,
-        limit,
-        output_path,
-    ) {
-        Ok(_) => {}
-        Err(e) => {
-            let msg = format!("Error: {}", e);
-            eprintln!("{}", msg.bright_red())
+    };
+
+    let source = match source {
+        Some(source) => source,
+        None => {
+            let schema = matches.opt_str("S").unwrap();
+
+            match extract_schema(
+                &connection,
+                &schema,
+                &matches.opt_strs("include"),
+                &matches.opt_strs("exclude"),
+                &options,
+                output_path,
+                force,
+            ) {
+                Ok(outcomes) => print_outcomes(&outcomes),
+                Err(e) => exit_with_error(e),
+            }
+
+            return;
+        }
+    };
+
+    if let Source::Table(table) = &source {
+        if listing_partitions {
+            match list_partitions(&connection, table) {
+                Ok(keys) => keys.iter().for_each(|key| println!("{}", key)),
+                Err(e) => exit_with_error(e),
+            }
+
+            return;
+        }
+
+        if splitting_partitions {
+            match extract_partitions(&connection, table, &options, output_path, force) {
+                Ok(outcomes) => print_outcomes(&outcomes),
+                Err(e) => exit_with_error(e),
+            }
+
+            return;
         }
     }
+
+    if let Err(e) = extract(&connection, &source, &options, output_path) {
+        exit_with_error(e);
+    }
+}
+
+fn run(job_file: &str, force: bool) {
+    let mut job = match Job::load(Path::new(job_file)) {
+        Ok(job) => job,
+        Err(e) => exit_with_error(e),
+    };
+
+    if job.connection.password.is_none() {
+        job.connection.password = get_password_from_user();
+    }
+
+    match run_job(&job, force) {
+        Ok(outcomes) => print_outcomes(&outcomes),
+        Err(e) => exit_with_error(e),
+    }
+}
+
+fn print_outcomes(outcomes: &[ExtractOutcome]) {
+    let mut failures = 0;
+
+    for outcome in outcomes {
+        match &outcome.result {
+            Ok(_) => println!(
+                "{} {} -> {}",
+                "ok".bright_green(),
+                outcome.name,
+                outcome.output_path.display()
+            ),
+            Err(e) => {
+                failures += 1;
+                let msg = format!("failed {}: {}", outcome.name, e);
+                eprintln!("{}", msg.bright_red());
+            }
+        }
+    }
+
+    let summary = format!(
+        "\n{} of {} extractions succeeded",
+        outcomes.len() - failures,
+        outcomes.len()
+    );
+
+    if failures == 0 {
+        println!("{}", summary.bright_green());
+    } else {
+        eprintln!("{}", summary.bright_red());
+        process::exit(1);
+    }
+}
+
+/// Report an error that ended the extraction, and exit with a failure status.
+fn exit_with_error(e: impl Display) -> ! {
+    let msg = format!("Error: {}", e);
+    eprintln!("{}", msg.bright_red());
+    process::exit(1);
 }
 
 fn get_password_from_user() -> Option<String> {
@@ -192,9 +666,57 @@ fn get_password_from_user() -> Option<String> {
     }
 }
 
+/// The CSV settings given on the command line, with defaults for the rest.
+fn csv_options(matches: &Matches) -> Result<CsvOptions, String> {
+    let mut csv = CsvOptions::default();
+
+    if let Some(delimiter) = matches.opt_str("delimiter") {
+        csv.delimiter = match delimiter.as_str() {
+            "\\t" => '\t',
+            _ => single_char(&delimiter).ok_or("the delimiter must be a single character")?,
+        };
+    }
+
+    if let Some(quote) = matches.opt_str("quote") {
+        csv.quote = single_char(&quote).ok_or("the quote must be a single character")?;
+    }
+
+    if let Some(quoting) = matches.opt_str("quoting") {
+        csv.quoting =
+            Quoting::parse(&quoting).ok_or("quoting must be one of necessary, always or never")?;
+    }
+
+    if let Some(null) = matches.opt_str("null") {
+        csv.null = null;
+    }
+
+    csv.header = matches.opt_present("header");
+    csv.date_format = matches.opt_str("date-format");
+    csv.timestamp_format = matches.opt_str("timestamp-format");
+
+    if let Some(encoding) = matches.opt_str("binary-encoding") {
+        csv.binary_encoding = BinaryEncoding::parse(&encoding)
+            .ok_or("binary encoding must be one of octal, hex or base64")?;
+    }
+
+    csv.validate()?;
+
+    Ok(csv)
+}
+
+fn single_char(string: &str) -> Option<char> {
+    let mut chars = string.chars();
+
+    match (chars.next(), chars.next()) {
+        (Some(c), None) => Some(c),
+        _ => None,
+    }
+}
+
 fn print_usage(program: &str, opts: Options) {
     let brief = format!(
-        "Usage: {} [options]\n\toptions with {} are required",
+        "Usage: {} [options]\n       {} run <jobfile> [-f]\n\toptions with {} are required",
+        program,
         program,
         "*".bright_red()
     );
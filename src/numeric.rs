@@ -0,0 +1,208 @@
+/// Vertica's precision for a `NUMERIC` declared without one.
+const DEFAULT_PRECISION: u16 = 37;
+
+/// The number of bytes a `NUMERIC` of this precision takes in the native format: one
+/// 64-bit word per 19 digits, plus one.
+pub(crate) fn numeric_width(precision: Option<u16>) -> u16 {
+    ((precision.unwrap_or(DEFAULT_PRECISION) / 19) + 1) * 8
+}
+
+/// Encode a `NUMERIC` value, as Vertica writes it as text (e.g. `-123.45`), into the
+/// native format: the unscaled value as a two's-complement integer of `width` bytes,
+/// in 64-bit words from most to least significant, each word little-endian.
+///
+/// Returns `None` if the value isn't a decimal number, has non-zero digits beyond
+/// `scale`, or doesn't fit in `width` bytes.
+pub(crate) fn encode_numeric(value: &str, width: u16, scale: u16) -> Option<Vec<u8>> {
+    let value = value.trim();
+
+    let (negative, value) = if let Some(value) = value.strip_prefix('-') {
+        (true, value)
+    } else if let Some(value) = value.strip_prefix('+') {
+        (false, value)
+    } else {
+        (false, value)
+    };
+
+    let mut parts = value.splitn(2, '.');
+    let whole = parts.next()?;
+    let fraction = parts.next().unwrap_or("");
+
+    if whole.is_empty() && fraction.is_empty() {
+        return None;
+    }
+
+    if !whole
+        .bytes()
+        .chain(fraction.bytes())
+        .all(|b| b.is_ascii_digit())
+    {
+        return None;
+    }
+
+    // the fraction must be exactly `scale` digits long; extra digits are only
+    // tolerated if they're zeros
+    let scale = scale as usize;
+    let (fraction, dropped) = if fraction.len() > scale {
+        fraction.split_at(scale)
+    } else {
+        (fraction, "")
+    };
+
+    if dropped.bytes().any(|b| b != b'0') {
+        return None;
+    }
+
+    let padding = "0".repeat(scale - fraction.len());
+    let digits = whole.bytes().chain(fraction.bytes()).chain(padding.bytes());
+
+    // the unscaled value, as 64-bit words from least to most significant
+    let mut words = vec![0_u64; width as usize / 8];
+
+    for digit in digits {
+        let mut carry = (digit - b'0') as u128;
+
+        for word in words.iter_mut() {
+            let product = (*word as u128) * 10 + carry;
+            *word = product as u64;
+            carry = product >> 64;
+        }
+
+        if carry != 0 {
+            return None;
+        }
+    }
+
+    // leave room for the sign bit
+    if words.last()? >> 63 != 0 {
+        return None;
+    }
+
+    if negative {
+        let mut carry = true;
+
+        for word in words.iter_mut() {
+            let (sum, overflow) = (!*word).overflowing_add(carry as u64);
+            *word = sum;
+            carry = overflow;
+        }
+    }
+
+    Some(
+        words
+            .iter()
+            .rev()
+            .flat_map(|word| word.to_le_bytes().to_vec())
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bytes written as hex pairs, e.g. `"c7 cf ff"`.
+    fn hex(string: &str) -> Vec<u8> {
+        string
+            .split_whitespace()
+            .map(|pair| u8::from_str_radix(pair, 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn widths() {
+        assert_eq!(numeric_width(Some(18)), 8);
+        assert_eq!(numeric_width(Some(19)), 16);
+        assert_eq!(numeric_width(None), 16);
+        assert_eq!(numeric_width(Some(1024)), 432);
+    }
+
+    #[test]
+    fn single_word() {
+        assert_eq!(
+            encode_numeric("0", 8, 0),
+            Some(hex("00 00 00 00 00 00 00 00"))
+        );
+        assert_eq!(
+            encode_numeric("+1.230", 8, 2),
+            Some(hex("7b 00 00 00 00 00 00 00"))
+        );
+        assert_eq!(
+            encode_numeric("1.2", 8, 2),
+            Some(hex("78 00 00 00 00 00 00 00"))
+        );
+        assert_eq!(
+            encode_numeric(".5", 8, 1),
+            Some(hex("05 00 00 00 00 00 00 00"))
+        );
+    }
+
+    #[test]
+    fn negative_with_scale() {
+        assert_eq!(
+            encode_numeric("-123.45", 8, 2),
+            Some(hex("c7 cf ff ff ff ff ff ff"))
+        );
+        assert_eq!(
+            encode_numeric("-0.01", 8, 2),
+            Some(hex("ff ff ff ff ff ff ff ff"))
+        );
+    }
+
+    #[test]
+    fn multiple_words() {
+        assert_eq!(
+            encode_numeric("12345678901234567890123", 16, 0),
+            Some(hex("9d 02 00 00 00 00 00 00 cb 44 42 71 76 4e b6 42"))
+        );
+        assert_eq!(
+            encode_numeric("-12345678901234567890123.4", 16, 1),
+            Some(hex("db e5 ff ff ff ff ff ff 0e 50 69 93 5f ef e0 64"))
+        );
+        // a carry into the second word
+        assert_eq!(
+            encode_numeric("18446744073709551616", 16, 0),
+            Some(hex("01 00 00 00 00 00 00 00 00 00 00 00 00 00 00 00"))
+        );
+        // a borrow through every word
+        assert_eq!(
+            encode_numeric("-1", 16, 0),
+            Some(hex("ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff ff"))
+        );
+    }
+
+    #[test]
+    fn sign_bit() {
+        assert_eq!(
+            encode_numeric("9223372036854775807", 8, 0),
+            Some(hex("ff ff ff ff ff ff ff 7f"))
+        );
+        assert_eq!(
+            encode_numeric("-9223372036854775807", 8, 0),
+            Some(hex("01 00 00 00 00 00 00 80"))
+        );
+        assert_eq!(encode_numeric("9223372036854775808", 8, 0), None);
+        assert_eq!(encode_numeric("-9223372036854775808", 8, 0), None);
+    }
+
+    #[test]
+    fn values_that_do_not_fit() {
+        assert_eq!(encode_numeric("18446744073709551616", 8, 0), None);
+        assert_eq!(
+            encode_numeric("170141183460469231731687303715884105728", 16, 0),
+            None
+        );
+        // scaling up can overflow too
+        assert_eq!(encode_numeric("922337203685477580.8", 8, 1), None);
+    }
+
+    #[test]
+    fn values_that_are_not_numbers() {
+        assert_eq!(encode_numeric("", 8, 0), None);
+        assert_eq!(encode_numeric(".", 8, 0), None);
+        assert_eq!(encode_numeric("-", 8, 0), None);
+        assert_eq!(encode_numeric("1e5", 8, 0), None);
+        assert_eq!(encode_numeric("1.2.3", 8, 2), None);
+        assert_eq!(encode_numeric("1.234", 8, 2), None);
+    }
+}
@@ -0,0 +1,31 @@
+use serde::Deserialize;
+
+/// The kind of file an extraction writes.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum OutputFormat {
+    /// Vertica's native binary format.
+    #[default]
+    Native,
+    /// Delimited text, as `COPY ... DELIMITER` loads it.
+    Csv,
+}
+
+impl OutputFormat {
+    /// Parse a `--format` value: `native` or `csv`.
+    pub fn parse(string: &str) -> Option<OutputFormat> {
+        match string.trim().to_lowercase().as_str() {
+            "native" => Some(OutputFormat::Native),
+            "csv" => Some(OutputFormat::Csv),
+            _ => None,
+        }
+    }
+
+    /// The extension of the files written in this format.
+    pub fn extension(self) -> &'static str {
+        match self {
+            OutputFormat::Native => "bin",
+            OutputFormat::Csv => "csv",
+        }
+    }
+}
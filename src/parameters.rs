@@ -0,0 +1,193 @@
+use crate::errors::Errors;
+
+/// Parse a `name=value` binding, such as the ones given to `--param`.
+pub fn parse_param(string: &str) -> Result<(String, String), Errors> {
+    match string.find('=') {
+        Some(index) if index > 0 => Ok((
+            string[..index].trim().to_string(),
+            string[(index + 1)..].to_string(),
+        )),
+        _ => Err(Errors::InvalidParameterError(string.to_string())),
+    }
+}
+
+/// Replace each `:name` placeholder in `predicate` with an ODBC `?` marker, and return
+/// the rewritten predicate along with the values to bind, in marker order.
+///
+/// Placeholders inside string literals, quoted identifiers and comments are left
+/// alone, as are `::` casts. Every placeholder must have a binding, and every binding must be used.
+pub(crate) fn bind_named_parameters(
+    predicate: &str,
+    params: &[(String, String)],
+) -> Result<(String, Vec<String>), Errors> {
+    let chars: Vec<char> = predicate.chars().collect();
+    let mut rewritten = String::with_capacity(predicate.len());
+    let mut values: Vec<String> = vec![];
+    let mut used = vec![false; params.len()];
+    let mut quote: Option<char> = None;
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+
+        match quote {
+            Some(q) => {
+                if c == q {
+                    quote = None;
+                }
+                rewritten.push(c);
+                i += 1;
+            }
+            None if c == '\'' || c == '"' => {
+                quote = Some(c);
+                rewritten.push(c);
+                i += 1;
+            }
+            None if c == '-' && chars.get(i + 1) == Some(&'-') => {
+                let end = (i..chars.len())
+                    .find(|&j| chars[j] == '\n')
+                    .unwrap_or(chars.len());
+
+                rewritten.extend(&chars[i..end]);
+                i = end;
+            }
+            None if c == '/' && chars.get(i + 1) == Some(&'*') => {
+                let end = (i + 2..chars.len())
+                    .find(|&j| chars[j] == '*' && chars.get(j + 1) == Some(&'/'))
+                    .map_or(chars.len(), |j| j + 2);
+
+                rewritten.extend(&chars[i..end]);
+                i = end;
+            }
+            None if c == ':' && chars.get(i + 1) == Some(&':') => {
+                rewritten.push_str("::");
+                i += 2;
+            }
+            None if c == ':' && chars.get(i + 1).is_some_and(|n| is_name_start(*n)) => {
+                let start = i + 1;
+                let mut end = start;
+
+                while end < chars.len() && is_name_part(chars[end]) {
+                    end += 1;
+                }
+
+                let name: String = chars[start..end].iter().collect();
+
+                let position = params
+                    .iter()
+                    .position(|(param, _)| param.eq_ignore_ascii_case(&name))
+                    .ok_or_else(|| Errors::MissingParameterError(name.clone()))?;
+
+                used[position] = true;
+                values.push(params[position].1.clone());
+                rewritten.push('?');
+                i = end;
+            }
+            None => {
+                rewritten.push(c);
+                i += 1;
+            }
+        }
+    }
+
+    if let Some(position) = used.iter().position(|u| !u) {
+        return Err(Errors::UnusedParameterError(params[position].0.clone()));
+    }
+
+    Ok((rewritten, values))
+}
+
+fn is_name_start(c: char) -> bool {
+    c.is_ascii_alphabetic() || c == '_'
+}
+
+fn is_name_part(c: char) -> bool {
+    c.is_ascii_alphanumeric() || c == '_'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(bindings: &[(&str, &str)]) -> Vec<(String, String)> {
+        bindings
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn parse_params() {
+        assert_eq!(
+            parse_param("start=2020-06-01").unwrap(),
+            ("start".to_string(), "2020-06-01".to_string())
+        );
+        assert_eq!(
+            parse_param("expr=a=b").unwrap(),
+            ("expr".to_string(), "a=b".to_string())
+        );
+        assert!(parse_param("=x").is_err());
+        assert!(parse_param("x").is_err());
+    }
+
+    #[test]
+    fn placeholders() {
+        let (sql, values) = bind_named_parameters(
+            "t >= :start and t < :end and id <> :start",
+            &params(&[("end", "2"), ("START", "1")]),
+        )
+        .unwrap();
+
+        assert_eq!(sql, "t >= ? and t < ? and id <> ?");
+        assert_eq!(values, vec!["1", "2", "1"]);
+    }
+
+    #[test]
+    fn casts() {
+        let (sql, values) =
+            bind_named_parameters("t::date = :day::date", &params(&[("day", "x")])).unwrap();
+
+        assert_eq!(sql, "t::date = ?::date");
+        assert_eq!(values, vec!["x"]);
+    }
+
+    #[test]
+    fn quoted_placeholders() {
+        let predicate = "a = ':x' and \":x\" = :y and b = 'it''s :x'";
+        let (sql, values) = bind_named_parameters(predicate, &params(&[("y", "1")])).unwrap();
+
+        assert_eq!(sql, "a = ':x' and \":x\" = ? and b = 'it''s :x'");
+        assert_eq!(values, vec!["1"]);
+    }
+
+    #[test]
+    fn commented_placeholders() {
+        let predicate = "a = :y -- or :x\nand /* :x */ b = :z /* :x";
+        let (sql, values) =
+            bind_named_parameters(predicate, &params(&[("y", "1"), ("z", "2")])).unwrap();
+
+        assert_eq!(sql, "a = ? -- or :x\nand /* :x */ b = ? /* :x");
+        assert_eq!(values, vec!["1", "2"]);
+    }
+
+    #[test]
+    fn missing_parameters() {
+        match bind_named_parameters("a = :x and b = :y", &params(&[("x", "1")])) {
+            Err(Errors::MissingParameterError(name)) => assert_eq!(name, "y"),
+            other => panic!("{:?}", other),
+        }
+    }
+
+    #[test]
+    fn unused_parameters() {
+        match bind_named_parameters("a = :x", &params(&[("x", "1"), ("y", "2")])) {
+            Err(Errors::UnusedParameterError(name)) => assert_eq!(name, "y"),
+            other => panic!("{:?}", other),
+        }
+
+        match bind_named_parameters("a = ':y'", &params(&[("y", "2")])) {
+            Err(Errors::UnusedParameterError(name)) => assert_eq!(name, "y"),
+            other => panic!("{:?}", other),
+        }
+    }
+}
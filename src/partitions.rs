@@ -0,0 +1,150 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use odbc::create_environment_v3;
+use odbc::odbc_safe::AutocommitOn;
+use odbc::Connection;
+
+use crate::connection_options::ConnectionOptions;
+use crate::errors::Errors;
+use crate::extract_options::ExtractOptions;
+use crate::extract_outcome::ExtractOutcome;
+use crate::source::Source;
+use crate::table_name::{quote_literal, TableName};
+use crate::{
+    extract_with_connection, fetch_rows, resolve_table, GET_PARTITION_EXPRESSION_QUERY,
+    GET_PARTITION_KEYS_QUERY,
+};
+
+/// The partition keys of a table, in order.
+pub fn list_partitions(
+    connection: &ConnectionOptions,
+    table: &str,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    let conn = env.connect_with_connection_string(&connection.dsn())?;
+
+    let table = resolve_table(&conn, table)?;
+
+    get_partition_keys(&conn, &table)
+}
+
+/// Extract each of a table's partitions into its own file in `output_dir`, named
+/// `<schema>.<table>.<partition key>.bin` (or `.csv`). Only the partitions in
+/// `options.partitions` are extracted, if it's given; otherwise all of them are.
+/// A failure with one partition doesn't stop the others.
+pub fn extract_partitions(
+    connection: &ConnectionOptions,
+    table: &str,
+    options: &ExtractOptions,
+    output_dir: &Path,
+    force: bool,
+) -> Result<Vec<ExtractOutcome>, Box<dyn Error>> {
+    let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    let conn = env.connect_with_connection_string(&connection.dsn())?;
+
+    let table = resolve_table(&conn, table)?;
+
+    let keys = match &options.partitions {
+        Some(keys) => keys.clone(),
+        None => get_partition_keys(&conn, &table)?,
+    };
+
+    fs::create_dir_all(output_dir)?;
+
+    let mut outcomes: Vec<ExtractOutcome> = vec![];
+
+    for key in keys {
+        let output_path = output_dir.join(format!(
+            "{}.{}.{}",
+            table,
+            file_name_safe(&key),
+            options.format.extension()
+        ));
+
+        let partition_options = ExtractOptions {
+            partitions: Some(vec![key.clone()]),
+            ..options.clone()
+        };
+
+        let result = if output_path.exists() && !force {
+            Err(Errors::OutputExistsError(output_path.display().to_string()).into())
+        } else {
+            extract_with_connection(
+                &conn,
+                &Source::Table(table.to_sql()),
+                &partition_options,
+                &output_path,
+            )
+        };
+
+        outcomes.push(ExtractOutcome {
+            name: format!("{} partition {}", table, key),
+            output_path,
+            result,
+        });
+    }
+
+    Ok(outcomes)
+}
+
+/// A predicate matching the rows in any of `count` partitions, whose keys are bound as
+/// parameters.
+pub(crate) fn create_partition_condition<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &TableName,
+    count: usize,
+) -> Result<String, Box<dyn Error>> {
+    let expression = get_partition_expression(conn, table)?;
+    let markers = vec!["?"; count].join(", ");
+
+    Ok(format!("({})::varchar in ({})", expression, markers))
+}
+
+fn get_partition_expression<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &TableName,
+) -> Result<String, Box<dyn Error>> {
+    let schema = table.schema.clone().unwrap_or_default();
+    let query = GET_PARTITION_EXPRESSION_QUERY
+        .replace("XX_SCHEMA_NAME_XX", quote_literal(&schema).as_str())
+        .replace("XX_TABLE_NAME_XX", quote_literal(&table.name).as_str());
+
+    match fetch_rows(conn, &query)?.first() {
+        Some(values) if !values[0].is_empty() => Ok(values[0].clone()),
+        _ => Err(Box::new(Errors::NotPartitionedError(table.to_string()))),
+    }
+}
+
+fn get_partition_keys<'env>(
+    conn: &Connection<'env, AutocommitOn>,
+    table: &TableName,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    // fail for unpartitioned tables, rather than silently listing nothing
+    get_partition_expression(conn, table)?;
+
+    let schema = table.schema.clone().unwrap_or_default();
+    let query = GET_PARTITION_KEYS_QUERY
+        .replace("XX_SCHEMA_NAME_XX", quote_literal(&schema).as_str())
+        .replace("XX_TABLE_NAME_XX", quote_literal(&table.name).as_str());
+
+    let keys = fetch_rows(conn, &query)?
+        .into_iter()
+        .map(|mut values| values.remove(0))
+        .collect();
+
+    Ok(keys)
+}
+
+fn file_name_safe(key: &str) -> String {
+    key.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+use crate::errors::Errors;
+
+/// How to pick a random subset of the rows being extracted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Sample {
+    /// Roughly this percentage of the rows.
+    Percent(f64),
+    /// Exactly this many rows (or all of them, if there are fewer).
+    Rows(usize),
+}
+
+impl Sample {
+    pub(crate) fn validate(&self) -> Result<(), Errors> {
+        match self {
+            Sample::Percent(percent) if !(*percent > 0.0 && *percent <= 100.0) => Err(
+                Errors::InvalidSampleError(format!("{}% is not between 0 and 100", percent)),
+            ),
+            Sample::Rows(0) => Err(Errors::InvalidSampleError(
+                "a sample must have at least one row".to_string(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// What to do with the next row fetched.
+pub(crate) enum Placement {
+    Write,
+    Skip,
+    /// Keep it in the reservoir, in this slot, replacing whatever was there.
+    Store(usize),
+}
+
+/// Client-side sampling, for when Vertica's `TABLESAMPLE` can't be used.
+///
+/// Percentages are sampled by keeping each row with that probability; a number of
+/// rows is sampled with a reservoir, so only that many rows are ever held in memory.
+/// Rows are placed before they're decoded, so skipped rows cost nothing but the fetch.
+pub(crate) struct Sampler {
+    sample: Sample,
+    rng: StdRng,
+    seen: usize,
+}
+
+impl Sampler {
+    pub(crate) fn new(sample: Sample, seed: Option<u64>) -> Sampler {
+        let rng = match seed {
+            None => StdRng::from_entropy(),
+            Some(seed) => StdRng::seed_from_u64(seed),
+        };
+
+        Sampler {
+            sample,
+            rng,
+            seen: 0,
+        }
+    }
+
+    pub(crate) fn place_next(&mut self) -> Placement {
+        let index = self.seen;
+        self.seen += 1;
+
+        match self.sample {
+            Sample::Percent(percent) => {
+                if self.rng.gen::<f64>() * 100.0 < percent {
+                    Placement::Write
+                } else {
+                    Placement::Skip
+                }
+            }
+            Sample::Rows(rows) if index < rows => Placement::Store(index),
+            Sample::Rows(rows) => {
+                let slot = self.rng.gen_range(0..=index);
+
+                if slot < rows {
+                    Placement::Store(slot)
+                } else {
+                    Placement::Skip
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use odbc::create_environment_v3;
+
+use crate::connection_options::ConnectionOptions;
+use crate::errors::Errors;
+use crate::extract_options::ExtractOptions;
+use crate::extract_outcome::ExtractOutcome;
+use crate::glob::glob_matches;
+use crate::source::Source;
+use crate::table_name::{quote_literal, TableName};
+use crate::{extract_with_connection, fetch_rows, GET_SCHEMA_TABLES_QUERY};
+
+/// Extract every table in `schema` into `<output_dir>/<schema>.<table>.bin` (or `.csv`).
+///
+/// A table is extracted if its name matches one of the `include` globs (or there are
+/// none) and none of the `exclude` globs. A failure with one table doesn't stop the
+/// others; each table's outcome is returned, in name order.
+pub fn extract_schema(
+    connection: &ConnectionOptions,
+    schema: &str,
+    include: &[String],
+    exclude: &[String],
+    options: &ExtractOptions,
+    output_dir: &Path,
+    force: bool,
+) -> Result<Vec<ExtractOutcome>, Box<dyn Error>> {
+    let env = create_environment_v3().map_err(|e| e.unwrap())?;
+    let conn = env.connect_with_connection_string(&connection.dsn())?;
+
+    let query =
+        GET_SCHEMA_TABLES_QUERY.replace("XX_SCHEMA_NAME_XX", quote_literal(schema).as_str());
+
+    let tables: Vec<TableName> = fetch_rows(&conn, &query)?
+        .iter()
+        .map(|values| TableName::qualified(&values[0], &values[1]))
+        .filter(|table| {
+            (include.is_empty() || include.iter().any(|g| glob_matches(g, &table.name)))
+                && !exclude.iter().any(|g| glob_matches(g, &table.name))
+        })
+        .collect();
+
+    if tables.is_empty() {
+        return Err(Box::new(Errors::NoTablesError(schema.to_string())));
+    }
+
+    fs::create_dir_all(output_dir)?;
+
+    let mut outcomes: Vec<ExtractOutcome> = vec![];
+
+    for table in tables {
+        let output_path = output_dir.join(format!("{}.{}", table, options.format.extension()));
+
+        let result = if output_path.exists() && !force {
+            Err(Errors::OutputExistsError(output_path.display().to_string()).into())
+        } else {
+            extract_with_connection(&conn, &Source::Table(table.to_sql()), options, &output_path)
+        };
+
+        outcomes.push(ExtractOutcome {
+            name: table.to_string(),
+            output_path,
+            result,
+        });
+    }
+
+    Ok(outcomes)
+}
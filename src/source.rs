@@ -0,0 +1,18 @@
+/// Where the rows to extract come from.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Source {
+    /// A table name; column types are read from the catalog.
+    Table(String),
+    /// An arbitrary `select`; column types are read from the result-set metadata.
+    Query(String),
+}
+
+impl Source {
+    /// Build a `Source::Query`, removing any trailing `;` so the text can be
+    /// embedded as a subquery.
+    pub fn query(text: &str) -> Source {
+        let trimmed = text.trim().trim_end_matches(';').trim_end();
+
+        Source::Query(trimmed.to_string())
+    }
+}
@@ -0,0 +1,66 @@
+use std::error::Error;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::column_type::ColumnType;
+use crate::sql_data_type::SqlDataType;
+use crate::table_name::quote_identifier;
+
+/// Where the `COPY` column list for a native file with spatial columns is written:
+/// next to it, as `<output>.copy.sql`.
+pub(crate) fn copy_columns_path(output_path: &Path) -> PathBuf {
+    PathBuf::from(format!("{}.copy.sql", output_path.display()))
+}
+
+/// Write the column list for loading a native file whose `geometry` and `geography`
+/// columns were extracted as WKB. Each spatial column is read into a filler and
+/// rebuilt with `ST_GeomFromWKB` or `ST_GeographyFromWKB`, e.g.
+///
+/// ```sql
+/// COPY locations ("id", "wkb_place" FILLER LONG VARBINARY(1048576),
+///     "place" AS ST_GeomFromWKB("wkb_place")) FROM '...' NATIVE;
+/// ```
+pub(crate) fn write_copy_columns(
+    column_types: &[ColumnType],
+    output_path: &Path,
+) -> Result<(), Box<dyn Error>> {
+    let mut columns: Vec<String> = vec![];
+
+    for column_type in column_types {
+        let name = quote_identifier(&column_type.name);
+
+        let function = match column_type.data_type {
+            SqlDataType::Geometry => "ST_GeomFromWKB",
+            SqlDataType::Geography => "ST_GeographyFromWKB",
+            _ => {
+                columns.push(name);
+                continue;
+            }
+        };
+
+        let filler = quote_identifier(&format!("wkb_{}", column_type.name));
+
+        columns.push(format!(
+            "{} filler long varbinary({})",
+            filler, column_type.width
+        ));
+        columns.push(format!("{} as {}({})", name, function, filler));
+    }
+
+    let text = format!(
+        "-- the column list for loading {} with COPY ... NATIVE\n({})\n",
+        output_path.display(),
+        columns.join(",\n ")
+    );
+
+    fs::write(copy_columns_path(output_path), text)?;
+
+    Ok(())
+}
+
+pub(crate) fn has_spatial_columns(column_types: &[ColumnType]) -> bool {
+    column_types.iter().any(|column_type| {
+        column_type.data_type == SqlDataType::Geometry
+            || column_type.data_type == SqlDataType::Geography
+    })
+}
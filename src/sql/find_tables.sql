@@ -0,0 +1,9 @@
+select table_schema,        -- 0
+       table_name           -- 1
+from (select table_schema, table_name from v_catalog.tables
+      union all
+      select table_schema, table_name from v_catalog.views
+      union all
+      select table_schema, table_name from v_catalog.system_tables) as relations
+where lower(table_name) = lower('XX_TABLE_NAME_XX')
+order by table_schema;
@@ -5,5 +5,20 @@ select column_name,         -- 0
        numeric_scale,       -- 4
        datetime_precision,  -- 5
        interval_precision   -- 6
-from v_catalog.columns
-where lower(table_name) = lower('XX_TABLE_NAME_XX');
\ No newline at end of file
+from (select table_schema, table_name, column_name, data_type, data_type_length,
+             numeric_precision, numeric_scale, datetime_precision, interval_precision,
+             ordinal_position
+      from v_catalog.columns
+      union all
+      select table_schema, table_name, column_name, data_type, data_type_length,
+             numeric_precision, numeric_scale, datetime_precision, interval_precision,
+             ordinal_position
+      from v_catalog.view_columns
+      union all
+      select table_schema, table_name, column_name, data_type, data_type_length,
+             numeric_precision, numeric_scale, datetime_precision, interval_precision,
+             ordinal_position
+      from v_catalog.system_columns) as relation_columns
+where lower(table_schema) = lower('XX_SCHEMA_NAME_XX')
+  and lower(table_name) = lower('XX_TABLE_NAME_XX')
+order by ordinal_position;
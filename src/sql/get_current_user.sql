@@ -0,0 +1 @@
+select current_user();
@@ -0,0 +1,4 @@
+select partition_expression -- 0
+from v_catalog.tables
+where lower(table_schema) = lower('XX_SCHEMA_NAME_XX')
+  and lower(table_name) = lower('XX_TABLE_NAME_XX');
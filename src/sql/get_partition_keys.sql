@@ -0,0 +1,7 @@
+select distinct partitions.partition_key    -- 0
+from v_monitor.partitions as partitions
+         join v_catalog.projections as projections
+              on projections.projection_id = partitions.projection_id
+where lower(projections.projection_schema) = lower('XX_SCHEMA_NAME_XX')
+  and lower(projections.anchor_table_name) = lower('XX_TABLE_NAME_XX')
+order by partitions.partition_key;
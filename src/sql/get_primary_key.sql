@@ -0,0 +1,5 @@
+select column_name          -- 0
+from v_catalog.primary_keys
+where lower(table_schema) = lower('XX_SCHEMA_NAME_XX')
+  and lower(table_name) = lower('XX_TABLE_NAME_XX')
+order by ordinal_position;
@@ -0,0 +1,5 @@
+select table_schema,        -- 0
+       table_name           -- 1
+from v_catalog.tables
+where lower(table_schema) = lower('XX_SCHEMA_NAME_XX')
+order by table_name;
@@ -0,0 +1 @@
+show search_path;
@@ -1 +1 @@
-select * from XX_TABLE_NAME_XX XX_LIMIT_XX;
\ No newline at end of file
+select XX_COLUMNS_XX from XX_TABLE_NAME_XX XX_TABLESAMPLE_XX XX_WHERE_XX XX_ORDER_BY_XX XX_LIMIT_XX;
\ No newline at end of file
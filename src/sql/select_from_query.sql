@@ -0,0 +1 @@
+select XX_COLUMNS_XX from (XX_QUERY_XX) as extract_query XX_WHERE_XX XX_ORDER_BY_XX XX_LIMIT_XX;
\ No newline at end of file
@@ -0,0 +1 @@
+set datestyle to iso;
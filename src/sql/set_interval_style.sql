@@ -0,0 +1 @@
+set intervalstyle to plain;
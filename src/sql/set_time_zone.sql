@@ -0,0 +1 @@
+set time zone to 'UTC';
@@ -1,8 +1,54 @@
+use odbc::ffi;
 use regex;
 use regex::Regex;
 
 use lazy_static::lazy_static;
 
+use crate::interval::{parse_qualifier, IntervalField};
+
+// ODBC SQL type codes, as `SQLDescribeCol` reports them
+const SQL_CHAR: i16 = ffi::SQL_CHAR as i16;
+const SQL_NUMERIC: i16 = ffi::SQL_NUMERIC as i16;
+const SQL_DECIMAL: i16 = ffi::SQL_DECIMAL as i16;
+const SQL_INTEGER: i16 = ffi::SQL_INTEGER as i16;
+const SQL_SMALLINT: i16 = ffi::SQL_SMALLINT as i16;
+const SQL_FLOAT: i16 = ffi::SQL_FLOAT as i16;
+const SQL_REAL: i16 = ffi::SQL_REAL as i16;
+const SQL_DOUBLE: i16 = ffi::SQL_DOUBLE as i16;
+const SQL_VARCHAR: i16 = ffi::SQL_VARCHAR as i16;
+const SQL_LONGVARCHAR: i16 = ffi::SQL_EXT_LONGVARCHAR as i16;
+const SQL_BINARY: i16 = ffi::SQL_EXT_BINARY as i16;
+const SQL_VARBINARY: i16 = ffi::SQL_EXT_VARBINARY as i16;
+const SQL_LONGVARBINARY: i16 = ffi::SQL_EXT_LONGVARBINARY as i16;
+const SQL_BIGINT: i16 = ffi::SQL_EXT_BIGINT as i16;
+const SQL_TINYINT: i16 = ffi::SQL_EXT_TINYINT as i16;
+const SQL_BIT: i16 = ffi::SQL_EXT_BIT as i16;
+const SQL_WCHAR: i16 = ffi::SQL_EXT_WCHAR as i16;
+const SQL_WVARCHAR: i16 = ffi::SQL_EXT_WVARCHAR as i16;
+const SQL_WLONGVARCHAR: i16 = ffi::SQL_EXT_WLONGVARCHAR as i16;
+const SQL_GUID: i16 = ffi::SQL_EXT_GUID as i16;
+// the ODBC 2 date and time codes, and their ODBC 3 `SQL_TYPE_*` replacements
+const SQL_DATE: i16 = 9;
+const SQL_TIME: i16 = 10;
+const SQL_TIMESTAMP: i16 = 11;
+const SQL_TYPE_DATE: i16 = 91;
+const SQL_TYPE_TIME: i16 = 92;
+const SQL_TYPE_TIMESTAMP: i16 = 93;
+// the interval codes, which `ffi::SqlDataType` doesn't list
+const SQL_INTERVAL_YEAR: i16 = 101;
+const SQL_INTERVAL_MONTH: i16 = 102;
+const SQL_INTERVAL_DAY: i16 = 103;
+const SQL_INTERVAL_HOUR: i16 = 104;
+const SQL_INTERVAL_MINUTE: i16 = 105;
+const SQL_INTERVAL_SECOND: i16 = 106;
+const SQL_INTERVAL_YEAR_TO_MONTH: i16 = 107;
+const SQL_INTERVAL_DAY_TO_HOUR: i16 = 108;
+const SQL_INTERVAL_DAY_TO_MINUTE: i16 = 109;
+const SQL_INTERVAL_DAY_TO_SECOND: i16 = 110;
+const SQL_INTERVAL_HOUR_TO_MINUTE: i16 = 111;
+const SQL_INTERVAL_HOUR_TO_SECOND: i16 = 112;
+const SQL_INTERVAL_MINUTE_TO_SECOND: i16 = 113;
+
 #[derive(Debug, PartialEq)]
 pub enum SqlDataType {
     Integer,
@@ -18,7 +64,18 @@ pub enum SqlDataType {
     Varbinary,
     Binary,
     Numeric,
-    Interval,
+    /// A day-time interval, with its leading field.
+    Interval(IntervalField),
+    /// A year-month interval, with its leading field.
+    IntervalYearMonth(IntervalField),
+    Uuid,
+    LongVarchar,
+    LongVarbinary,
+    /// Spatial types, which are extracted as WKB.
+    Geometry,
+    Geography,
+    /// A type that can't be extracted, by name.
+    Unsupported(String),
 }
 
 impl SqlDataType {
@@ -27,11 +84,21 @@ impl SqlDataType {
             static ref PAREN_REGEX: Regex = Regex::new(r"\(.+\)").unwrap();
         }
 
-        let no_parens = PAREN_REGEX.replace(string, "");
+        let no_parens = PAREN_REGEX.replace(string, "").to_lowercase();
+
+        if let Some(qualifier) = no_parens.strip_prefix("interval") {
+            return match parse_qualifier(qualifier) {
+                Some((leading, _)) if leading.is_year_month() => {
+                    SqlDataType::IntervalYearMonth(leading)
+                }
+                Some((leading, _)) => SqlDataType::Interval(leading),
+                None => SqlDataType::Unsupported(string.to_string()),
+            };
+        }
 
-        match no_parens.to_lowercase().as_str() {
-            "int" => SqlDataType::Integer,
-            "float" => SqlDataType::Float,
+        match no_parens.as_str() {
+            "int" | "integer" | "bigint" | "smallint" | "tinyint" | "int8" => SqlDataType::Integer,
+            "float" | "float8" | "real" | "double precision" => SqlDataType::Float,
             "char" => SqlDataType::Char,
             "varchar" => SqlDataType::Varchar,
             "boolean" => SqlDataType::Boolean,
@@ -42,11 +109,53 @@ impl SqlDataType {
             "timetz" => SqlDataType::TimeTz,
             "varbinary" => SqlDataType::Varbinary,
             "binary" => SqlDataType::Binary,
-            "numeric" => SqlDataType::Numeric,
-            "interval" => SqlDataType::Interval,
-            "interval year to month" => SqlDataType::Interval,
-            "interval day to minute" => SqlDataType::Interval,
-            _ => panic!("unknown data type"),
+            "numeric" | "decimal" => SqlDataType::Numeric,
+            "uuid" => SqlDataType::Uuid,
+            "long varchar" => SqlDataType::LongVarchar,
+            "long varbinary" => SqlDataType::LongVarbinary,
+            "geometry" => SqlDataType::Geometry,
+            "geography" => SqlDataType::Geography,
+            _ => SqlDataType::Unsupported(string.to_string()),
+        }
+    }
+
+    /// Map an ODBC SQL type code, as reported by `SQLDescribeCol`, onto a Vertica type.
+    ///
+    /// ODBC doesn't distinguish `timestamp` from `timestamptz` (or `time` from `timetz`),
+    /// so those come back as their zone-less variants; values with a UTC offset are
+    /// still written correctly, as they're read as text.
+    pub(crate) fn from_odbc(data_type: i16) -> Option<SqlDataType> {
+        match data_type {
+            SQL_BIGINT | SQL_INTEGER | SQL_SMALLINT | SQL_TINYINT => Some(SqlDataType::Integer),
+            SQL_FLOAT | SQL_REAL | SQL_DOUBLE => Some(SqlDataType::Float),
+            SQL_CHAR | SQL_WCHAR => Some(SqlDataType::Char),
+            SQL_VARCHAR | SQL_WVARCHAR => Some(SqlDataType::Varchar),
+            SQL_LONGVARCHAR | SQL_WLONGVARCHAR => Some(SqlDataType::LongVarchar),
+            SQL_BIT => Some(SqlDataType::Boolean),
+            SQL_TYPE_DATE | SQL_DATE => Some(SqlDataType::Date),
+            SQL_TYPE_TIMESTAMP | SQL_TIMESTAMP => Some(SqlDataType::Timestamp),
+            SQL_TYPE_TIME | SQL_TIME => Some(SqlDataType::Time),
+            SQL_VARBINARY => Some(SqlDataType::Varbinary),
+            SQL_LONGVARBINARY => Some(SqlDataType::LongVarbinary),
+            SQL_BINARY => Some(SqlDataType::Binary),
+            SQL_NUMERIC | SQL_DECIMAL => Some(SqlDataType::Numeric),
+            SQL_GUID => Some(SqlDataType::Uuid),
+            SQL_INTERVAL_YEAR | SQL_INTERVAL_YEAR_TO_MONTH => {
+                Some(SqlDataType::IntervalYearMonth(IntervalField::Year))
+            }
+            SQL_INTERVAL_MONTH => Some(SqlDataType::IntervalYearMonth(IntervalField::Month)),
+            SQL_INTERVAL_DAY
+            | SQL_INTERVAL_DAY_TO_HOUR
+            | SQL_INTERVAL_DAY_TO_MINUTE
+            | SQL_INTERVAL_DAY_TO_SECOND => Some(SqlDataType::Interval(IntervalField::Day)),
+            SQL_INTERVAL_HOUR | SQL_INTERVAL_HOUR_TO_MINUTE | SQL_INTERVAL_HOUR_TO_SECOND => {
+                Some(SqlDataType::Interval(IntervalField::Hour))
+            }
+            SQL_INTERVAL_MINUTE | SQL_INTERVAL_MINUTE_TO_SECOND => {
+                Some(SqlDataType::Interval(IntervalField::Minute))
+            }
+            SQL_INTERVAL_SECOND => Some(SqlDataType::Interval(IntervalField::Second)),
+            _ => None,
         }
     }
 }
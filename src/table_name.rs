@@ -0,0 +1,139 @@
+/// A table name, optionally qualified with the schema it lives in.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TableName {
+    pub(crate) schema: Option<String>,
+    pub(crate) name: String,
+}
+
+impl TableName {
+    /// Parse `table` or `schema.table`; surrounding double quotes are removed from
+    /// each part, and a `.` inside quotes doesn't separate them.
+    pub fn parse(string: &str) -> TableName {
+        let (schema, name) = match find_separator(string) {
+            None => (None, string),
+            Some(index) => (Some(unquote(&string[..index])), &string[(index + 1)..]),
+        };
+
+        TableName {
+            schema,
+            name: unquote(name),
+        }
+    }
+
+    pub fn qualified(schema: &str, name: &str) -> TableName {
+        TableName {
+            schema: Some(schema.to_string()),
+            name: name.to_string(),
+        }
+    }
+
+    /// The name as a quoted SQL identifier, e.g. `"public"."events"`.
+    pub fn to_sql(&self) -> String {
+        match &self.schema {
+            None => quote_identifier(&self.name),
+            Some(schema) => format!(
+                "{}.{}",
+                quote_identifier(schema),
+                quote_identifier(&self.name)
+            ),
+        }
+    }
+}
+
+impl std::fmt::Display for TableName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.schema {
+            None => write!(f, "{}", self.name),
+            Some(schema) => write!(f, "{}.{}", schema, self.name),
+        }
+    }
+}
+
+/// The position of the first `.` that isn't inside a quoted identifier. A doubled
+/// quote inside quotes toggles twice, so it doesn't end the identifier.
+fn find_separator(string: &str) -> Option<usize> {
+    let mut quoted = false;
+
+    for (index, c) in string.char_indices() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => return Some(index),
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn unquote(string: &str) -> String {
+    let trimmed = string.trim();
+
+    if trimmed.len() >= 2 && trimmed.starts_with('"') && trimmed.ends_with('"') {
+        trimmed[1..(trimmed.len() - 1)].replace("\"\"", "\"")
+    } else {
+        trimmed.to_string()
+    }
+}
+
+pub(crate) fn quote_identifier(identifier: &str) -> String {
+    format!("\"{}\"", identifier.replace('"', "\"\""))
+}
+
+pub(crate) fn quote_literal(literal: &str) -> String {
+    literal.replace('\'', "''")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unqualified() {
+        assert_eq!(
+            TableName::parse("events"),
+            TableName {
+                schema: None,
+                name: "events".to_string()
+            }
+        );
+        assert_eq!(TableName::parse("\"my.table\"").name, "my.table");
+    }
+
+    #[test]
+    fn qualified() {
+        assert_eq!(TableName::parse("a.b"), TableName::qualified("a", "b"));
+        assert_eq!(TableName::parse(" a . b "), TableName::qualified("a", "b"));
+    }
+
+    #[test]
+    fn dots_inside_quotes() {
+        assert_eq!(
+            TableName::parse("\"my.schema\".t"),
+            TableName::qualified("my.schema", "t")
+        );
+        assert_eq!(
+            TableName::parse("s.\"my.table\""),
+            TableName::qualified("s", "my.table")
+        );
+    }
+
+    #[test]
+    fn doubled_quotes() {
+        assert_eq!(
+            TableName::parse("\"a\"\"b\".c"),
+            TableName::qualified("a\"b", "c")
+        );
+        assert_eq!(
+            TableName::parse("\"a\"\".b\".c"),
+            TableName::qualified("a\".b", "c")
+        );
+    }
+
+    #[test]
+    fn round_trip() {
+        let table = TableName::parse("\"a\"\"b\".\"x.y\"");
+
+        assert_eq!(table.to_sql(), "\"a\"\"b\".\"x.y\"");
+        assert_eq!(TableName::parse(&table.to_sql()), table);
+    }
+}
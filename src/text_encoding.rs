@@ -0,0 +1,97 @@
+use std::str;
+
+use encoding_rs::{Encoding, UTF_8};
+
+/// How the bytes of character columns are to be understood. Native files hold UTF-8,
+/// which is also what Vertica stores, but older loads may have put other encodings
+/// into `char` and `varchar` columns.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TextEncoding {
+    /// Values must be valid UTF-8; any that aren't fail the extraction.
+    #[default]
+    Utf8,
+    /// Values are written byte for byte, whatever they hold.
+    Raw,
+    /// Values are in ISO-8859-1, each byte being the code point of the same number.
+    /// WHATWG (and so `encoding_rs`) takes `latin1` to mean windows-1252, which would
+    /// turn 0x80 to 0x9F into other characters.
+    Latin1,
+    /// Values are in this encoding, and are transcoded to UTF-8.
+    Named(&'static Encoding),
+}
+
+/// Labels that mean ISO-8859-1 itself, rather than windows-1252.
+const LATIN1_LABELS: &[&str] = &[
+    "latin1",
+    "l1",
+    "iso-8859-1",
+    "iso8859-1",
+    "iso88591",
+    "iso_8859-1",
+    "iso_8859-1:1987",
+    "iso-ir-100",
+    "csisolatin1",
+    "cp819",
+    "ibm819",
+];
+
+impl TextEncoding {
+    /// Parse an `--encoding` value: `raw`, or the name of an encoding, such as
+    /// `utf-8` or `latin1`.
+    pub fn parse(string: &str) -> Option<TextEncoding> {
+        if string.trim().eq_ignore_ascii_case("raw") {
+            return Some(TextEncoding::Raw);
+        }
+
+        if LATIN1_LABELS
+            .iter()
+            .any(|label| string.trim().eq_ignore_ascii_case(label))
+        {
+            return Some(TextEncoding::Latin1);
+        }
+
+        match Encoding::for_label(string.trim().as_bytes()) {
+            Some(encoding) if encoding == UTF_8 => Some(TextEncoding::Utf8),
+            Some(encoding) => Some(TextEncoding::Named(encoding)),
+            None => None,
+        }
+    }
+
+    pub(crate) fn name(&self) -> &'static str {
+        match self {
+            TextEncoding::Utf8 => "UTF-8",
+            TextEncoding::Raw => "raw",
+            TextEncoding::Latin1 => "ISO-8859-1",
+            TextEncoding::Named(encoding) => encoding.name(),
+        }
+    }
+
+    /// The most UTF-8 bytes one byte of a value can become: 2 for ISO-8859-1, and 3
+    /// for any other encoding (e.g. windows-1252's 0x80 is `€`, U+20AC).
+    pub(crate) fn max_growth(&self) -> u32 {
+        match self {
+            TextEncoding::Utf8 | TextEncoding::Raw => 1,
+            TextEncoding::Latin1 => 2,
+            TextEncoding::Named(_) => 3,
+        }
+    }
+
+    /// The UTF-8 bytes of a value, or `None` if it isn't valid in this encoding.
+    pub(crate) fn decode(&self, bytes: Vec<u8>) -> Option<Vec<u8>> {
+        match self {
+            TextEncoding::Utf8 if str::from_utf8(&bytes).is_ok() => Some(bytes),
+            TextEncoding::Utf8 => None,
+            TextEncoding::Raw => Some(bytes),
+            TextEncoding::Latin1 => Some(
+                bytes
+                    .iter()
+                    .map(|b| *b as char)
+                    .collect::<String>()
+                    .into_bytes(),
+            ),
+            TextEncoding::Named(encoding) => encoding
+                .decode_without_bom_handling_and_without_replacement(&bytes)
+                .map(|text| text.into_owned().into_bytes()),
+        }
+    }
+}
@@ -0,0 +1,26 @@
+use serde::Deserialize;
+
+/// What to do with columns whose type can't be written to the output.
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum UnsupportedTypes {
+    /// Refuse to extract, naming every unsupported column.
+    #[default]
+    Fail,
+    /// Leave the columns out.
+    Skip,
+    /// Have Vertica cast the columns to `varchar`.
+    Cast,
+}
+
+impl UnsupportedTypes {
+    /// Parse an `--unsupported-types` value: `fail`, `skip` or `cast`.
+    pub fn parse(string: &str) -> Option<UnsupportedTypes> {
+        match string.trim().to_lowercase().as_str() {
+            "fail" => Some(UnsupportedTypes::Fail),
+            "skip" => Some(UnsupportedTypes::Skip),
+            "cast" => Some(UnsupportedTypes::Cast),
+            _ => None,
+        }
+    }
+}
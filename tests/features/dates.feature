@@ -0,0 +1,24 @@
+Feature: Dates and timestamps across Vertica's whole range
+  Background:
+    Given I have waited no more than 300 seconds for Vertica to be ready
+    And there are no tables
+
+  Scenario Outline: Extracting the <type> <value>
+    Given a table "dates_test" with a column "d" of type "<type>" holding '<value>'
+    When I extract the table "dates_test"
+    Then column 1 of row 1 is "<native>"
+
+    Examples:
+      | type      | value                    | native                  |
+      | date      | 2000-01-01               | 00 00 00 00 00 00 00 00 |
+      | date      | 1999-12-31               | ff ff ff ff ff ff ff ff |
+      | date      | 0001-12-31 BC            | f8 db f4 ff ff ff ff ff |
+      | date      | 4713-01-01 BC            | cd 97 da ff ff ff ff ff |
+      | date      | infinity                 | ff ff ff ff ff ff ff 7f |
+      | date      | -infinity                | 01 00 00 00 00 00 00 80 |
+      | timestamp | 2020-06-01 12:34:56.5    | 20 1d 85 ca 03 4a 02 00 |
+      | timestamp | 2024-02-29 00:00:00      | 00 60 8c b5 78 b5 02 00 |
+      | timestamp | 0001-01-01 00:00:00 BC   | 00 20 b1 1b 3d c6 1f ff |
+      | timestamp | 294276-12-31 23:59:59    | c0 5d a3 b3 5b ff ff 7f |
+      | timestamp | infinity                 | ff ff ff ff ff ff ff 7f |
+      | timestamp | -infinity                | 01 00 00 00 00 00 00 80 |
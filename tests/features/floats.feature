@@ -0,0 +1,21 @@
+Feature: Floats are written bit for bit
+  Background:
+    Given I have waited no more than 300 seconds for Vertica to be ready
+    And there are no tables
+
+  Scenario Outline: Extracting the float <value>
+    Given a table "floats_test" with a column "f" of type "float" holding '<value>'
+    When I extract the table "floats_test"
+    Then column 1 of row 1 is "<native>"
+
+    Examples:
+      | value                   | native                  |
+      | 1.5                     | 00 00 00 00 00 00 f8 3f |
+      | 0                       | 00 00 00 00 00 00 00 00 |
+      | -0.0                    | 00 00 00 00 00 00 00 80 |
+      | NaN                     | 00 00 00 00 00 00 f8 7f |
+      | Infinity                | 00 00 00 00 00 00 f0 7f |
+      | -Infinity               | 00 00 00 00 00 00 f0 ff |
+      | 4.9e-324                | 01 00 00 00 00 00 00 00 |
+      | 2.2250738585072009e-308 | ff ff ff ff ff ff 0f 00 |
+      | 1.7976931348623157e308  | ff ff ff ff ff ff ef 7f |
@@ -0,0 +1,22 @@
+Feature: Intervals are written as microseconds or months
+  Background:
+    Given I have waited no more than 300 seconds for Vertica to be ready
+    And there are no tables
+
+  Scenario Outline: Extracting the <type> <value>
+    Given a table "intervals_test" with a column "i" of type "<type>" holding '<value>'
+    When I extract the table "intervals_test"
+    Then column 1 of row 1 is "<native>"
+
+    Examples:
+      | type                      | value                   | native                  |
+      | interval day to second    | 1 hour                  | 00 a4 93 d6 00 00 00 00 |
+      | interval day to second    | 1.5 seconds             | 60 e3 16 00 00 00 00 00 |
+      | interval day to second    | -1 hour                 | 00 5c 6c 29 ff ff ff ff |
+      | interval day to second    | 0 seconds               | 00 00 00 00 00 00 00 00 |
+      | interval day to second    | 1 day 2 hours 3 minutes | 00 3d b9 d5 15 00 00 00 |
+      | interval day to second    | -26 hours               | 00 58 01 35 ea ff ff ff |
+      | interval hour to minute   | 1 hour                  | 00 a4 93 d6 00 00 00 00 |
+      | interval minute to second | 90 seconds              | 80 4a 5d 05 00 00 00 00 |
+      | interval year to month    | 1 year 2 months         | 0e 00 00 00 00 00 00 00 |
+      | interval year to month    | -14 months              | f2 ff ff ff ff ff ff ff |
@@ -0,0 +1,72 @@
+require "open3"
+
+EXTRACTOR = ENV.fetch("VERTICA_EXTRACTOR", "/verticaextractor")
+NATIVE_SIGNATURE = "NATIVE\n\xFF\r\n\x00".b
+
+After do
+  (@created_tables || []).each { |table| vertica_query("drop table if exists #{table} cascade;") }
+end
+
+Given(/^a table "(\w+)" with a column "(\w+)" of type "([^"]+)" holding '(.*)'$/) do |table, column, type, value|
+  (@created_tables ||= []) << table
+  vertica_query("create table #{table} (#{column} #{type}); insert into #{table} values ('#{value}'); commit;")
+end
+
+# The output file is removed first, so one left by an earlier example can't pass for it.
+When(/^I extract the table "(\w+)"$/) do |table|
+  @output_file = "/tmp/#{table}.bin"
+  File.delete(@output_file) if File.exist?(@output_file)
+
+  _, stderr, status = Open3.capture3(
+    EXTRACTOR, "-s", "vertica", "-d", ENV.fetch("VERTICA_DB", "docker"),
+    "-u", ENV.fetch("VERTICA_USER", "dbadmin"), "-P", ENV.fetch("VERTICA_PASSWORD", ""),
+    "-t", table, "-o", @output_file, "-f"
+  )
+
+  expect(status.success?).to be(true), stderr
+  expect(stderr).to be_empty
+  expect(File.exist?(@output_file)).to be(true), "#{@output_file} wasn't written"
+end
+
+Then(/^column (\d+) of row (\d+) is "([0-9a-f ]+)"$/) do |column, row, hex|
+  rows = read_native_file(@output_file)
+  expect(rows[row.to_i - 1][column.to_i - 1].unpack1("H*")).to eq(hex.delete(" "))
+end
+
+# The values of every row in a native file, as binary strings (`nil` for nulls).
+def read_native_file(path)
+  data = File.binread(path)
+  expect(data[0, 11]).to eq(NATIVE_SIGNATURE)
+
+  header_length = data[11, 4].unpack1("L<")
+  column_count = data[18, 2].unpack1("S<")
+  widths = data[20, column_count * 4].unpack("L<*")
+  offset = 15 + header_length
+  bitmap_length = (column_count + 7) / 8
+
+  rows = []
+
+  while offset < data.length
+    row_length = data[offset, 4].unpack1("L<")
+    bitmap = data[offset + 4, bitmap_length].unpack1("B*")
+    position = offset + 4 + bitmap_length
+
+    values = widths.each_with_index.map do |width, index|
+      next nil if bitmap[index] == "1"
+
+      if width == 0xFFFFFFFF
+        width = data[position, 4].unpack1("L<")
+        position += 4
+      end
+
+      value = data[position, width]
+      position += width
+      value
+    end
+
+    rows << values
+    offset += 4 + row_length
+  end
+
+  rows
+end
@@ -0,0 +1,17 @@
+Feature: TIMETZ values are written with their own UTC offset
+  Background:
+    Given I have waited no more than 300 seconds for Vertica to be ready
+    And there are no tables
+
+  Scenario Outline: Extracting a TIMETZ with offset <offset>
+    Given a table "timetz_test" with a column "t" of type "timetz" holding '<value>'
+    When I extract the table "timetz_test"
+    Then column 1 of row 1 is "<native>"
+
+    Examples:
+      | offset | value                 | native                  |
+      | -05    | 15:12:34-05           | d0 97 01 80 f0 79 f0 10 |
+      | +05:30 | 09:30:00+05:30        | 28 04 01 00 90 4e 5a 03 |
+      | -03:30 | 00:00:00-03:30        | b8 82 01 00 be 04 ef 02 |
+      | +14    | 12:00:00.5+14         | a0 8c 00 20 b9 b7 70 12 |
+      | +00    | 23:59:59.999999+00    | 80 51 01 ff 5f d7 1d 14 |